@@ -1,15 +1,17 @@
 use crate::analyze::{
-    CommitAnalyzed, DataAnalysis, PullRequestAnalyzed, SprintsAnalyzed, UserDataAnalyzed,
-    UsersAnalyzed,
+    CommitAnalyzed, DataAnalysis, PullRequestAnalyzed, SprintsAnalyzed, UnmatchedAuthor,
+    UnmatchedAuthors, UserDataAnalyzed, UsersAnalyzed,
 };
+use crate::forge::{PullRequest, ReviewState};
 use crate::git::Commit;
-use crate::gitea::{GiteaPullRequest, PullRequest};
 use crate::model::{Sprint, User};
-use chrono::DateTime;
-use gitea_sdk::model::reviews::ReviewStateType;
+use chrono::{DateTime, FixedOffset};
+use indexmap::IndexMap;
 
 pub trait Analyzer {
     fn analyze_sprints(&self) -> SprintsAnalyzed;
+    /// Authors of commits in the sprints no user matches, the most changed lines first.
+    fn unmatched_authors(&self) -> UnmatchedAuthors;
 }
 
 impl Analyzer for DataAnalysis {
@@ -18,19 +20,25 @@ impl Analyzer for DataAnalysis {
         for sprint in &self.sprints {
             let mut users_analyzed: UsersAnalyzed = vec![];
             for user in &self.users {
-                let commits = self.commits_from_user_in_sprint(&user, &sprint);
-                let commits_analysis = CommitAnalyzed::from_commits(commits);
+                let commits = self.commits_from_user_in_sprint(user, sprint);
+                let commits_analysis = CommitAnalyzed::from_commits(
+                    commits,
+                    user,
+                    self.co_author_credit,
+                    &self.languages,
+                    &self.commit_types,
+                );
 
                 let mut pull_request_analysis = PullRequestAnalyzed::default();
-                let pull_requests = self.pull_requests_from_user_in_sprint(&user, &sprint);
+                let pull_requests = self.pull_requests_from_user_in_sprint(user, sprint);
                 for pull_request in &pull_requests {
-                    pull_request_analysis.analyze_request(&pull_request.request, &sprint);
-                    pull_request_analysis.analyze_received_discussion(&pull_request, &sprint);
+                    pull_request_analysis.analyze_request(pull_request, sprint);
+                    pull_request_analysis.analyze_received_discussion(pull_request, sprint);
                 }
 
-                let pull_requests = self.pull_requests_closed_in_sprint(&sprint);
+                let pull_requests = self.pull_requests_closed_in_sprint(sprint);
                 for pull_request in &pull_requests {
-                    pull_request_analysis.analyze_reviews(&pull_request, &sprint, &user);
+                    pull_request_analysis.analyze_reviews(pull_request, sprint, user);
                 }
 
                 users_analyzed.push((
@@ -42,6 +50,35 @@ impl Analyzer for DataAnalysis {
         }
         sprints_analyzed
     }
+
+    fn unmatched_authors(&self) -> UnmatchedAuthors {
+        let mut authors: IndexMap<String, UnmatchedAuthor> = IndexMap::new();
+        let commits = self
+            .commits
+            .values()
+            .flatten()
+            .filter(|commit| {
+                self.sprints.iter().any(|sprint| {
+                    commit.datetime >= sprint.since && commit.datetime <= sprint.until
+                })
+            })
+            .filter(|commit| !self.users.iter().any(|user| user.is_email_owner(&commit.email)));
+        for commit in commits {
+            let email = commit.email.to_lowercase();
+            let author = authors
+                .entry(email.clone())
+                .or_insert_with(|| UnmatchedAuthor::new(email));
+            author.commits += 1;
+            author.change_lines += commit.insertions + commit.deletions;
+        }
+        let mut authors = authors.into_values().collect::<Vec<_>>();
+        authors.sort_by(|a, b| {
+            b.change_lines
+                .cmp(&a.change_lines)
+                .then_with(|| a.email.cmp(&b.email))
+        });
+        authors
+    }
 }
 
 trait DataAnalysisExtension {
@@ -59,9 +96,12 @@ trait DataAnalysisExtension {
 impl DataAnalysisExtension for DataAnalysis {
     fn commits_from_user_in_sprint<'a>(&'a self, user: &User, sprint: &Sprint) -> Vec<&'a Commit> {
         self.commits
-            .iter()
-            .flat_map(|(_, commits)| commits)
-            .filter(|commit| user.emails.contains(&commit.email))
+            .values()
+            .flatten()
+            .filter(|commit| {
+                user.is_email_owner(&commit.email)
+                    || commit.co_authors.iter().any(|email| user.is_email_owner(email))
+            })
             .filter(|commit| commit.datetime >= sprint.since && commit.datetime <= sprint.until)
             .collect::<Vec<_>>()
     }
@@ -72,12 +112,11 @@ impl DataAnalysisExtension for DataAnalysis {
         sprint: &Sprint,
     ) -> Vec<&'a PullRequest> {
         self.pull_requests
-            .iter()
-            .flat_map(|(_, pull_requests)| pull_requests)
-            .filter(|pull_request| user.emails.contains(&pull_request.request.user.email))
+            .values()
+            .flatten()
+            .filter(|pull_request| user.is_forge_user(&pull_request.author))
             .filter(|pull_request| {
-                let pull_request = pull_request.request.clone();
-                datetime_include_sprint(&Some(pull_request.created_at.to_string()), sprint)
+                datetime_include_sprint(&Some(pull_request.created_at), sprint)
                     || datetime_include_sprint(&pull_request.merged_at, sprint)
                     || datetime_include_sprint(&pull_request.closed_at, sprint)
             })
@@ -86,25 +125,22 @@ impl DataAnalysisExtension for DataAnalysis {
 
     fn pull_requests_closed_in_sprint<'a>(&'a self, sprint: &Sprint) -> Vec<&'a PullRequest> {
         self.pull_requests
-            .iter()
-            .flat_map(|(_, pull_requests)| pull_requests)
-            .filter(|pull_request| {
-                let pull_request = pull_request.request.clone();
-                datetime_include_sprint(&pull_request.closed_at, sprint)
-            })
+            .values()
+            .flatten()
+            .filter(|pull_request| datetime_include_sprint(&pull_request.closed_at, sprint))
             .collect::<Vec<_>>()
     }
 }
 
 trait PullRequestAnalyzer {
-    fn analyze_request(&mut self, pull_request: &GiteaPullRequest, sprint: &Sprint);
+    fn analyze_request(&mut self, pull_request: &PullRequest, sprint: &Sprint);
     fn analyze_received_discussion(&mut self, pull_request: &PullRequest, sprint: &Sprint);
     fn analyze_reviews(&mut self, pull_request: &PullRequest, sprint: &Sprint, user: &User);
 }
 
 impl PullRequestAnalyzer for PullRequestAnalyzed {
-    fn analyze_request(&mut self, pull_request: &GiteaPullRequest, sprint: &Sprint) {
-        if datetime_include_sprint(&Some(pull_request.created_at.to_string()), sprint) {
+    fn analyze_request(&mut self, pull_request: &PullRequest, sprint: &Sprint) {
+        if datetime_include_sprint(&Some(pull_request.created_at), sprint) {
             self.create_pull_requests += 1;
         }
         if datetime_include_sprint(&pull_request.merged_at, sprint)
@@ -120,9 +156,9 @@ impl PullRequestAnalyzer for PullRequestAnalyzed {
     }
 
     fn analyze_received_discussion(&mut self, pull_request: &PullRequest, sprint: &Sprint) {
-        if datetime_include_sprint(&pull_request.request.closed_at, sprint) {
+        if datetime_include_sprint(&pull_request.closed_at, sprint) {
             for review in &pull_request.reviews {
-                self.received_discussions += review.comments_count as usize
+                self.received_discussions += review.comments_count
             }
         }
     }
@@ -131,24 +167,26 @@ impl PullRequestAnalyzer for PullRequestAnalyzed {
         let mut assigned = false;
         let mut conducted = false;
         let mut added_discussions = 0;
-        if datetime_include_sprint(&pull_request.request.closed_at, sprint) {
+        if datetime_include_sprint(&pull_request.closed_at, sprint) {
             for review in &pull_request.reviews {
-                if let Some(review_user) = &review.user {
-                    if user.emails.contains(&review_user.email) {
+                if let Some(review_user) = &review.author {
+                    if user.is_forge_user(review_user) {
                         assigned = true;
                         match review.state {
-                            ReviewStateType::Approved => {
+                            ReviewState::Approved => {
                                 conducted = true;
                             }
-                            ReviewStateType::Pending => {}
-                            ReviewStateType::Comment => {}
-                            ReviewStateType::RequestChanges => {
+                            ReviewState::Pending => {}
+                            ReviewState::Comment => {}
+                            ReviewState::RequestChanges => {
                                 conducted = true;
                             }
-                            ReviewStateType::RequestReview => {}
-                            ReviewStateType::Unknown => {}
+                            ReviewState::RequestReview => {}
+                            // A dismissed verdict no longer counts as a review.
+                            ReviewState::Dismissed => {}
+                            ReviewState::Unknown => {}
                         }
-                        added_discussions += review.comments_count as usize
+                        added_discussions += review.comments_count
                     }
                 }
             }
@@ -159,10 +197,9 @@ impl PullRequestAnalyzer for PullRequestAnalyzed {
     }
 }
 
-fn datetime_include_sprint(datetime: &Option<String>, sprint: &Sprint) -> bool {
+fn datetime_include_sprint(datetime: &Option<DateTime<FixedOffset>>, sprint: &Sprint) -> bool {
     let Some(datetime) = datetime else {
         return false;
     };
-    let datetime = DateTime::parse_from_rfc3339(&datetime).unwrap();
-    datetime >= sprint.since && datetime <= sprint.until
+    *datetime >= sprint.since && *datetime <= sprint.until
 }
@@ -0,0 +1,237 @@
+use crate::analyze::{
+    CoupledFiles, DataAnalysis, HotspotThresholds, PathChanges, RepoHotspots, SprintsHotspots,
+};
+use crate::git::Commit;
+use crate::model::{Repository, Sprint, User};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub trait HotspotAnalyzer {
+    /// Hotspots of the commits the team members authored or co-authored.
+    fn analyze_hotspots(&self, team: &str, thresholds: &HotspotThresholds) -> SprintsHotspots;
+}
+
+impl HotspotAnalyzer for DataAnalysis {
+    fn analyze_hotspots(&self, team: &str, thresholds: &HotspotThresholds) -> SprintsHotspots {
+        let members = self
+            .users
+            .iter()
+            .filter(|user| user.teams.iter().any(|t| t == team))
+            .collect::<Vec<_>>();
+        let mut sprints_hotspots: SprintsHotspots = vec![];
+        for sprint in &self.sprints {
+            let mut repos_hotspots = vec![];
+            for repo in &self.repos {
+                let commits = self.team_commits_in_sprint(repo, sprint, &members);
+                if commits.is_empty() {
+                    continue;
+                }
+                repos_hotspots.push(RepoHotspots::from_commits(repo, &commits, thresholds));
+            }
+            sprints_hotspots.push((sprint.clone(), repos_hotspots));
+        }
+        sprints_hotspots
+    }
+}
+
+trait DataAnalysisExtension {
+    fn team_commits_in_sprint<'a>(
+        &'a self,
+        repo: &Repository,
+        sprint: &Sprint,
+        members: &[&User],
+    ) -> Vec<&'a Commit>;
+}
+
+impl DataAnalysisExtension for DataAnalysis {
+    fn team_commits_in_sprint<'a>(
+        &'a self,
+        repo: &Repository,
+        sprint: &Sprint,
+        members: &[&User],
+    ) -> Vec<&'a Commit> {
+        self.commits
+            .get(repo)
+            .into_iter()
+            .flatten()
+            .filter(|commit| commit.datetime >= sprint.since && commit.datetime <= sprint.until)
+            .filter(|commit| {
+                members.iter().any(|user| {
+                    user.is_email_owner(&commit.email)
+                        || commit
+                            .co_authors
+                            .iter()
+                            .any(|email| user.is_email_owner(email))
+                })
+            })
+            .collect()
+    }
+}
+
+// Create
+impl RepoHotspots {
+    fn from_commits(
+        repo: &Repository,
+        commits: &[&Commit],
+        thresholds: &HotspotThresholds,
+    ) -> Self {
+        let mut files: HashMap<&str, usize> = HashMap::new();
+        let mut directories: HashMap<String, usize> = HashMap::new();
+        let mut pairs: HashMap<(&str, &str), usize> = HashMap::new();
+        for commit in commits {
+            for file in &commit.files {
+                *files.entry(file).or_default() += 1;
+            }
+            let commit_directories = commit
+                .files
+                .iter()
+                .flat_map(|file| ancestor_directories(file))
+                .unique();
+            for directory in commit_directories {
+                *directories.entry(directory).or_default() += 1;
+            }
+            if commit.files.len() <= thresholds.coupling_max_files {
+                let commit_files = commit.files.iter().map(String::as_str).sorted().dedup();
+                for (first, second) in commit_files.tuple_combinations() {
+                    *pairs.entry((first, second)).or_default() += 1;
+                }
+            }
+        }
+        let coupling = pairs
+            .into_iter()
+            .filter(|(_, commits)| *commits >= thresholds.coupling_min_commits)
+            .map(|((first, second), commits)| CoupledFiles {
+                first: first.to_string(),
+                second: second.to_string(),
+                commits,
+                degree: commits * 200 / (files[first] + files[second]),
+            })
+            .filter(|coupled| coupled.degree >= thresholds.coupling_min_degree)
+            .sorted_by(|a, b| {
+                (b.commits, b.degree, &a.first, &a.second)
+                    .cmp(&(a.commits, a.degree, &b.first, &b.second))
+            })
+            .take(thresholds.top)
+            .collect();
+        Self {
+            repository: repo.name.clone(),
+            files: top_paths(files, thresholds.top),
+            directories: top_paths(directories, thresholds.top),
+            coupling,
+        }
+    }
+}
+
+/// Every directory above the file, so a change rolls up to the top-level ones. `.` stands
+/// for the root only for files right in it, it would top the list otherwise.
+fn ancestor_directories(file: &str) -> Vec<String> {
+    let directories = Path::new(file)
+        .ancestors()
+        .skip(1)
+        .filter(|directory| !directory.as_os_str().is_empty())
+        .map(|directory| directory.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    if directories.is_empty() {
+        vec![".".to_string()]
+    } else {
+        directories
+    }
+}
+
+fn top_paths(paths: HashMap<impl ToString, usize>, top: usize) -> Vec<PathChanges> {
+    paths
+        .into_iter()
+        .map(|(path, commits)| PathChanges {
+            path: path.to_string(),
+            commits,
+        })
+        .sorted_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.path.cmp(&b.path)))
+        .take(top)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_record, repository, BASE_TIME};
+
+    fn hotspots(thresholds: HotspotThresholds) -> RepoHotspots {
+        let commits = [
+            vec!["a", "b"],
+            vec!["a", "b"],
+            vec!["a", "c"],
+            vec!["a"],
+            vec!["b", "c", "d", "e"],
+        ]
+        .iter()
+        .map(|files| commit_record("alice", BASE_TIME, files))
+        .collect::<Vec<_>>();
+        let commits = commits.iter().collect::<Vec<_>>();
+        RepoHotspots::from_commits(&repository("app"), &commits, &thresholds)
+    }
+
+    fn coupling(hotspots: &RepoHotspots) -> Vec<(&str, &str, usize, usize)> {
+        hotspots
+            .coupling
+            .iter()
+            .map(|c| (c.first.as_str(), c.second.as_str(), c.commits, c.degree))
+            .collect()
+    }
+
+    #[test]
+    fn coupling_degree_and_filters() {
+        // `a` changes in 4 commits and `b` in 3, 2 shared ones make 2 * 200 / (4 + 3).
+        let shared_twice = hotspots(HotspotThresholds::new(10, 2, 0, 3));
+        assert_eq!(coupling(&shared_twice), vec![("a", "b", 2, 57)]);
+
+        // The commit of 4 files is left out.
+        let small_commits = hotspots(HotspotThresholds::new(10, 1, 0, 3));
+        assert_eq!(
+            coupling(&small_commits),
+            vec![("a", "b", 2, 57), ("a", "c", 1, 33)]
+        );
+
+        let all_commits = hotspots(HotspotThresholds::new(10, 1, 50, 4));
+        assert_eq!(
+            coupling(&all_commits),
+            vec![
+                ("a", "b", 2, 57),
+                ("d", "e", 1, 100),
+                ("c", "d", 1, 66),
+                ("c", "e", 1, 66),
+                ("b", "d", 1, 50),
+                ("b", "e", 1, 50),
+            ]
+        );
+    }
+
+    #[test]
+    fn top_truncates_every_list() {
+        let hotspots = hotspots(HotspotThresholds::new(2, 1, 0, 4));
+        let paths = |paths: &[PathChanges]| {
+            paths
+                .iter()
+                .map(|p| (p.path.clone(), p.commits))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            paths(&hotspots.files),
+            vec![("a".to_string(), 4), ("b".to_string(), 3)]
+        );
+        assert_eq!(paths(&hotspots.directories), vec![(".".to_string(), 5)]);
+        assert_eq!(
+            coupling(&hotspots),
+            vec![("a", "b", 2, 57), ("d", "e", 1, 100)]
+        );
+    }
+
+    #[test]
+    fn directories_roll_up_to_top_level() {
+        assert_eq!(
+            ancestor_directories("src/git/commit.rs"),
+            vec!["src/git", "src"]
+        );
+        assert_eq!(ancestor_directories("Cargo.toml"), vec!["."]);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::analyze::{
+    DataAnalysis, KnowledgeThresholds, ModuleOwner, SprintsKnowledge, TeamKnowledge, UserBreadth,
+};
+use crate::git::Ownership;
+use crate::model::{Repository, Sprint, User};
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashMap};
+
+pub trait KnowledgeAnalyzer {
+    /// Modules only one team member knows and what the members worked on.
+    fn analyze_knowledge(&self, team: &str, thresholds: &KnowledgeThresholds) -> SprintsKnowledge;
+}
+
+impl KnowledgeAnalyzer for DataAnalysis {
+    fn analyze_knowledge(&self, team: &str, thresholds: &KnowledgeThresholds) -> SprintsKnowledge {
+        let members = self
+            .users
+            .iter()
+            .filter(|user| user.teams.iter().any(|t| t == team))
+            .collect::<Vec<_>>();
+        self.sprints
+            .iter()
+            .map(|sprint| {
+                let bus_factor = self
+                    .repos
+                    .iter()
+                    .filter_map(|repo| Some((repo, self.ownership_at_sprint_end(repo, sprint)?)))
+                    .flat_map(|(repo, ownership)| {
+                        self.module_owners(repo, ownership, &members, thresholds)
+                    })
+                    .sorted_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.module.cmp(&b.module)))
+                    .collect();
+                let breadth = members
+                    .iter()
+                    .map(|user| self.user_breadth(user, sprint))
+                    .collect();
+                let knowledge = TeamKnowledge {
+                    bus_factor,
+                    breadth,
+                };
+                (sprint.clone(), knowledge)
+            })
+            .collect()
+    }
+}
+
+trait DataAnalysisExtension {
+    fn ownership_at_sprint_end(&self, repo: &Repository, sprint: &Sprint) -> Option<&Ownership>;
+
+    fn module_owners(
+        &self,
+        repo: &Repository,
+        ownership: &Ownership,
+        members: &[&User],
+        thresholds: &KnowledgeThresholds,
+    ) -> Vec<ModuleOwner>;
+
+    fn user_breadth(&self, user: &User, sprint: &Sprint) -> UserBreadth;
+}
+
+impl DataAnalysisExtension for DataAnalysis {
+    fn ownership_at_sprint_end(&self, repo: &Repository, sprint: &Sprint) -> Option<&Ownership> {
+        // The latest blame by the sprint end, a snapshot may have been taken for other sprints.
+        self.ownership
+            .get(repo)?
+            .iter()
+            .filter(|ownership| ownership.at <= sprint.until)
+            .max_by_key(|ownership| ownership.at)
+    }
+
+    fn module_owners(
+        &self,
+        repo: &Repository,
+        ownership: &Ownership,
+        members: &[&User],
+        thresholds: &KnowledgeThresholds,
+    ) -> Vec<ModuleOwner> {
+        // Lines per module and user, lines of unknown authors only count in the total.
+        let mut modules: HashMap<String, (usize, HashMap<&str, usize>)> = HashMap::new();
+        for (directory, emails) in &ownership.directories {
+            let (total, users) = modules
+                .entry(module(directory, thresholds.module_depth))
+                .or_default();
+            for (email, lines) in emails {
+                *total += lines;
+                if let Some(user) = self.users.iter().find(|user| user.is_email_owner(email)) {
+                    *users.entry(&user.username).or_default() += lines;
+                }
+            }
+        }
+        modules
+            .into_iter()
+            .filter(|(_, (total, _))| *total > 0 && *total >= thresholds.min_lines)
+            .filter_map(|(module, (total, users))| {
+                let (owner, lines) = users.into_iter().max_by_key(|(_, lines)| *lines)?;
+                let share = lines * 100 / total;
+                let is_member = members.iter().any(|user| user.username == owner);
+                (is_member && share > thresholds.owner_share).then(|| ModuleOwner {
+                    repository: repo.name.clone(),
+                    module,
+                    lines: total,
+                    owner: owner.to_string(),
+                    share,
+                })
+            })
+            .collect()
+    }
+
+    fn user_breadth(&self, user: &User, sprint: &Sprint) -> UserBreadth {
+        let mut repositories = BTreeSet::new();
+        let mut modules = BTreeSet::new();
+        for repo in &self.repos {
+            let commits = self
+                .commits
+                .get(repo)
+                .into_iter()
+                .flatten()
+                .filter(|commit| commit.datetime >= sprint.since && commit.datetime <= sprint.until)
+                .filter(|commit| {
+                    user.is_email_owner(&commit.email)
+                        || commit
+                            .co_authors
+                            .iter()
+                            .any(|email| user.is_email_owner(email))
+                });
+            for commit in commits {
+                repositories.insert(repo.name.clone());
+                for file in &commit.files {
+                    let module = match file.split_once('/') {
+                        Some((module, _)) => module,
+                        None => ".",
+                    };
+                    modules.insert(format!("{}/{module}", repo.name));
+                }
+            }
+        }
+        UserBreadth {
+            username: user.username.clone(),
+            repositories: repositories.into_iter().collect(),
+            modules: modules.into_iter().collect(),
+        }
+    }
+}
+
+/// The first `depth` components of the directory.
+fn module(directory: &str, depth: usize) -> String {
+    directory.split('/').take(depth.max(1)).join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{CommitStatsCache, GitCommitRepository, GitOwnershipRepository};
+    use crate::model::{CoAuthorCredit, MergePolicy};
+    use crate::test_support::{commit, git_repository, query, repository, user, BASE_TIME};
+    use chrono::DateTime;
+    use std::ops::RangeInclusive;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn sprint(name: &str, since: i64, until: i64) -> Sprint {
+        let datetime = |time| DateTime::from_timestamp(time, 0).unwrap().fixed_offset();
+        Sprint {
+            name: name.to_string(),
+            since: datetime(since),
+            until: datetime(until),
+        }
+    }
+
+    fn lines(prefix: &str, numbers: RangeInclusive<usize>) -> String {
+        numbers.map(|i| format!("{prefix}{i}\n")).collect()
+    }
+
+    #[test]
+    fn owners_and_breadth_at_sprint_end() {
+        let (dir, repo) = git_repository();
+        let core = lines("a", 1..=8);
+        let first = commit(
+            &repo,
+            "main",
+            &[],
+            "alice",
+            BASE_TIME,
+            &[("core/a.rs", &core), ("web/b.rs", &lines("a", 1..=4))],
+        );
+        // Bob rewrites 3 of 8 lines of `core` and ends up with 6 of 8 in `web`.
+        let bob_core = lines("b", 1..=3) + &lines("a", 4..=8);
+        let bob_web = lines("b", 1..=2) + &lines("a", 3..=4);
+        let second = commit(
+            &repo,
+            "main",
+            &[first],
+            "bob",
+            BASE_TIME + 10 * DAY,
+            &[
+                ("core/a.rs", &bob_core),
+                ("web/b.rs", &bob_web),
+                ("web/c.rs", &lines("c", 1..=4)),
+            ],
+        );
+        // Committed after the second sprint, its ownership must not see it.
+        commit(
+            &repo,
+            "main",
+            &[second],
+            "alice",
+            BASE_TIME + 20 * DAY,
+            &[("core/a.rs", &core), ("web/b.rs", &lines("a", 1..=4))],
+        );
+
+        let app = repository("app");
+        let sprints = vec![
+            sprint("first", BASE_TIME - DAY, BASE_TIME + 5 * DAY),
+            sprint("second", BASE_TIME + 5 * DAY, BASE_TIME + 14 * DAY),
+        ];
+        let query = query(MergePolicy::Full);
+        let at = sprints.iter().map(|s| s.until).collect::<Vec<_>>();
+        let ownership = repo.get_ownership(&query, &at).unwrap();
+        assert_eq!(ownership[1].commit, second.to_string());
+        let mut cache = CommitStatsCache::open(dir.path().join("stats.json"), "");
+        let (commits, _) = repo.get_commits(&query, &mut cache).unwrap();
+
+        let mut data = DataAnalysis::new(
+            vec![user("alice", "core"), user("bob", "core")],
+            sprints,
+            vec![app.clone()],
+            vec![],
+            vec![],
+            CoAuthorCredit::Full,
+        );
+        data.insert_commits(&app, commits);
+        data.insert_ownership(&app, ownership);
+
+        let owners = |owner_share| {
+            let thresholds = KnowledgeThresholds::new(owner_share, 5, 1);
+            data.analyze_knowledge("core", &thresholds)
+                .into_iter()
+                .map(|(_, knowledge)| {
+                    knowledge
+                        .bus_factor
+                        .into_iter()
+                        .map(|o| (o.module, o.lines, o.owner, o.share))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        let owner = |module: &str, lines, owner: &str, share| {
+            (module.to_string(), lines, owner.to_string(), share)
+        };
+        // `web` has fewer lines than the minimum in the first sprint.
+        assert_eq!(
+            owners(60),
+            vec![
+                vec![owner("core", 8, "alice", 100)],
+                vec![owner("core", 8, "alice", 62), owner("web", 8, "bob", 75)],
+            ]
+        );
+        // The owner has to exceed the share.
+        assert_eq!(owners(62)[1], vec![owner("web", 8, "bob", 75)]);
+
+        let knowledge = data.analyze_knowledge("core", &KnowledgeThresholds::new(60, 5, 1));
+        let breadth = knowledge
+            .iter()
+            .map(|(_, knowledge)| {
+                knowledge
+                    .breadth
+                    .iter()
+                    .map(|b| (b.username.as_str(), b.repositories.len(), b.modules.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let modules = |modules: &[&str]| modules.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            breadth,
+            vec![
+                vec![
+                    ("alice", 1, modules(&["app/core", "app/web"])),
+                    ("bob", 0, vec![]),
+                ],
+                vec![
+                    ("alice", 0, vec![]),
+                    ("bob", 1, modules(&["app/core", "app/web"])),
+                ],
+            ]
+        );
+    }
+}
@@ -1,4 +1,6 @@
 pub mod model;
 pub mod analyzer;
+pub mod hotspots;
+pub mod knowledge;
 
 pub use model::*;
@@ -1,28 +1,61 @@
-use crate::git::Commit;
-use crate::gitea::PullRequest;
-use crate::model::{Repository, Sprint, User};
-use std::collections::HashMap;
+use crate::git::{Commit, LineStats, Ownership};
+use crate::forge::PullRequest;
+use crate::model::{
+    CoAuthorCredit, CommitType, ConventionalCommit, Language, Repository, Sprint, User,
+};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// Language of the file types no configured or builtin language has.
+pub const OTHER_LANGUAGE: &str = "Other";
+/// Commit type of the conventional commits no configured or builtin type has.
+pub const OTHER_COMMIT_TYPE: &str = "other";
 
 pub type RepoCommits = HashMap<Repository, Vec<Commit>>;
 pub type RepoPullRequests = HashMap<Repository, Vec<PullRequest>>;
+pub type RepoOwnership = HashMap<Repository, Vec<Ownership>>;
+pub type RepoFailures = Vec<(Repository, String)>;
+pub type UnmatchedAuthors = Vec<UnmatchedAuthor>;
 
 #[derive(Debug, Clone)]
 pub struct DataAnalysis {
     pub users: Vec<User>,
     pub sprints: Vec<Sprint>,
     pub repos: Vec<Repository>,
+    /// Configured languages first, then the builtin ones.
+    pub languages: Vec<Language>,
+    /// Configured commit types first, then the builtin ones.
+    pub commit_types: Vec<CommitType>,
+    pub co_author_credit: CoAuthorCredit,
     pub commits: RepoCommits,
     pub pull_requests: RepoPullRequests,
+    /// Blamed trees at the sprint ends, empty unless the ownership was fetched.
+    pub ownership: RepoOwnership,
+    pub failures: RepoFailures,
 }
 
 impl DataAnalysis {
-    pub fn new(users: Vec<User>, sprints: Vec<Sprint>, repos: Vec<Repository>) -> Self {
+    pub fn new(
+        users: Vec<User>,
+        sprints: Vec<Sprint>,
+        repos: Vec<Repository>,
+        languages: Vec<Language>,
+        commit_types: Vec<CommitType>,
+        co_author_credit: CoAuthorCredit,
+    ) -> Self {
         Self {
             users,
             sprints,
             repos,
+            languages: [languages, Language::builtin()].concat(),
+            commit_types: [commit_types, CommitType::builtin()].concat(),
+            co_author_credit,
             commits: HashMap::new(),
             pull_requests: HashMap::new(),
+            ownership: HashMap::new(),
+            failures: vec![],
         }
     }
 
@@ -30,6 +63,10 @@ impl DataAnalysis {
         self.commits.insert(repository.clone(), commits);
     }
 
+    pub fn insert_ownership(&mut self, repository: &Repository, ownership: Vec<Ownership>) {
+        self.ownership.insert(repository.clone(), ownership);
+    }
+
     pub fn insert_pull_request(
         &mut self,
         repository: &Repository,
@@ -37,6 +74,10 @@ impl DataAnalysis {
     ) {
         self.pull_requests.insert(repository.clone(), pull_requests);
     }
+
+    pub fn insert_failure(&mut self, repository: &Repository, error: impl ToString) {
+        self.failures.push((repository.clone(), error.to_string()));
+    }
 }
 
 pub type SprintsAnalyzed = Vec<(Sprint, UsersAnalyzed)>;
@@ -52,15 +93,37 @@ impl UserDataAnalyzed {
     pub fn new(commits: CommitAnalyzed, pull_requests: PullRequestAnalyzed) -> Self {
         Self { commits, pull_requests, }
     }
+
+    pub fn default() -> Self {
+        Self::new(CommitAnalyzed::default(), PullRequestAnalyzed::default())
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CommitAnalyzed {
     pub files_changed: usize,
     pub change_lines: usize,
     pub insertions: usize,
     pub deletions: usize,
     pub commits: usize,
+    /// Commits with `Co-authored-by` trailers, as the author or a co-author.
+    pub co_authored_commits: usize,
+    /// Lines per language, the most changed first.
+    pub languages: IndexMap<String, LineStats>,
+    /// Inserted lines that change no recently written line, zero without the churn analysis.
+    /// Rework counts the replaced old lines instead, so the lines inserted in place of recent
+    /// ones are in neither.
+    pub new_work: usize,
+    /// Recently written lines of the user changed or deleted by the user.
+    pub rework_own: usize,
+    /// Recently written lines of others changed or deleted by the user.
+    pub rework_others: usize,
+    /// Commits with Conventional Commits headers, the rest don't follow the format.
+    pub conventional_commits: usize,
+    /// Conventional commits marked as breaking changes.
+    pub breaking_changes: usize,
+    /// Conventional commits per commit type, the most commits first.
+    pub commit_types: IndexMap<String, CommitTypeStats>,
 }
 
 impl CommitAnalyzed {
@@ -70,6 +133,8 @@ impl CommitAnalyzed {
         insertions: usize,
         deletions: usize,
         commits: usize,
+        co_authored_commits: usize,
+        languages: IndexMap<String, LineStats>,
     ) -> Self {
         Self {
             files_changed,
@@ -77,26 +142,130 @@ impl CommitAnalyzed {
             insertions,
             deletions,
             commits,
+            co_authored_commits,
+            languages,
+            new_work: 0,
+            rework_own: 0,
+            rework_others: 0,
+            conventional_commits: 0,
+            breaking_changes: 0,
+            commit_types: IndexMap::new(),
         }
     }
 
     pub fn default() -> Self {
-        Self::new(0, 0, 0, 0, 0)
+        Self::new(0, 0, 0, 0, 0, 0, IndexMap::new())
     }
 
-    pub fn from_commits(commits: Vec<&Commit>) -> Self {
-        commits.iter().fold(Self::default(), |mut acc, c| {
-            acc.files_changed += c.files_changed;
-            acc.change_lines += c.insertions + c.deletions;
-            acc.insertions += c.insertions;
-            acc.deletions += c.deletions;
+    /// Sums the commits the user authored or co-authored, lines and files are credited per
+    /// `credit` while every co-author counts the commit itself.
+    pub fn from_commits(
+        commits: Vec<&Commit>,
+        user: &User,
+        credit: CoAuthorCredit,
+        languages: &[Language],
+        commit_types: &[CommitType],
+    ) -> Self {
+        let mut analyzed = commits.iter().fold(Self::default(), |mut acc, c| {
+            let share = |lines: usize| match credit {
+                CoAuthorCredit::Full => lines,
+                CoAuthorCredit::Split => {
+                    let parts = c.co_authors.len() + 1;
+                    let share = lines / parts;
+                    if user.is_email_owner(&c.email) {
+                        lines - share * (parts - 1)
+                    } else {
+                        share
+                    }
+                }
+            };
+            acc.files_changed += share(c.files_changed);
+            acc.change_lines += share(c.insertions) + share(c.deletions);
+            acc.insertions += share(c.insertions);
+            acc.deletions += share(c.deletions);
             acc.commits += 1;
+            if !c.co_authors.is_empty() {
+                acc.co_authored_commits += 1;
+            }
+            if let Some(churn) = &c.churn {
+                acc.new_work += share(churn.new_work);
+                for (email, lines) in &churn.rework {
+                    if user.is_email_owner(email) {
+                        acc.rework_own += share(*lines);
+                    } else {
+                        acc.rework_others += share(*lines);
+                    }
+                }
+            }
+            for (file_type, lines) in &c.extensions {
+                let name = Language::find(languages, file_type)
+                    .map_or(OTHER_LANGUAGE, |language| &language.name);
+                let acc_lines = acc.languages.entry(name.to_string()).or_default();
+                acc_lines.insertions += share(lines.insertions);
+                acc_lines.deletions += share(lines.deletions);
+            }
+            if let Some(conventional) = ConventionalCommit::parse(&c.message) {
+                acc.conventional_commits += 1;
+                if conventional.breaking {
+                    acc.breaking_changes += 1;
+                }
+                let name = CommitType::find(commit_types, &conventional.kind)
+                    .map_or(OTHER_COMMIT_TYPE, |commit_type| &commit_type.name);
+                let stats = acc.commit_types.entry(name.to_string()).or_default();
+                stats.commits += 1;
+                stats.change_lines += share(c.insertions) + share(c.deletions);
+                stats.scopes.extend(conventional.scope);
+            }
             acc
-        })
+        });
+        analyzed.languages = analyzed
+            .languages
+            .into_iter()
+            .sorted_by(|(a_name, a), (b_name, b)| {
+                (b.insertions + b.deletions)
+                    .cmp(&(a.insertions + a.deletions))
+                    .then_with(|| a_name.cmp(b_name))
+            })
+            .collect();
+        analyzed.commit_types = CommitTypeStats::sorted(analyzed.commit_types);
+        analyzed
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CommitTypeStats {
+    pub commits: usize,
+    pub change_lines: usize,
+    /// Scopes of the headers, like `api` of `feat(api): ...`.
+    pub scopes: BTreeSet<String>,
+}
+
+impl CommitTypeStats {
+    /// Commit types of the users summed up, co-authored commits count for every co-author.
+    pub fn team<'a>(
+        commits: impl IntoIterator<Item = &'a CommitAnalyzed>,
+    ) -> IndexMap<String, CommitTypeStats> {
+        let mut team: IndexMap<String, CommitTypeStats> = IndexMap::new();
+        for (name, stats) in commits.into_iter().flat_map(|c| &c.commit_types) {
+            let team_stats = team.entry(name.clone()).or_default();
+            team_stats.commits += stats.commits;
+            team_stats.change_lines += stats.change_lines;
+            team_stats.scopes.extend(stats.scopes.iter().cloned());
+        }
+        Self::sorted(team)
+    }
+
+    fn sorted(commit_types: IndexMap<String, Self>) -> IndexMap<String, Self> {
+        commit_types
+            .into_iter()
+            .sorted_by(|(a_name, a), (b_name, b)| {
+                b.commits.cmp(&a.commits).then_with(|| a_name.cmp(b_name))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct PullRequestAnalyzed {
     pub create_pull_requests: usize,
     pub merged_pull_requests: usize,
@@ -132,3 +301,159 @@ impl PullRequestAnalyzed {
         Self::new(0, 0, 0, 0, 0, 0, 0)
     }
 }
+
+/// Commit author email in the sprints that belongs to no user.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnmatchedAuthor {
+    pub email: String,
+    pub commits: usize,
+    pub change_lines: usize,
+}
+
+impl UnmatchedAuthor {
+    pub fn new(email: impl ToString) -> Self {
+        Self {
+            email: email.to_string(),
+            commits: 0,
+            change_lines: 0,
+        }
+    }
+}
+
+pub type SprintsHotspots = Vec<(Sprint, Vec<RepoHotspots>)>;
+
+/// Limits of the hotspot analysis.
+#[derive(Debug, Clone, Copy)]
+pub struct HotspotThresholds {
+    /// Longest list of files, directories and coupled pairs per repository.
+    pub top: usize,
+    /// Fewest commits a pair of files shares to count as coupled.
+    pub coupling_min_commits: usize,
+    /// Lowest share of shared commits in the average commits of the two files, in percent.
+    pub coupling_min_degree: usize,
+    /// Commits changing more files are left out of the coupling, like bulk renames.
+    pub coupling_max_files: usize,
+}
+
+impl HotspotThresholds {
+    pub fn new(
+        top: usize,
+        coupling_min_commits: usize,
+        coupling_min_degree: usize,
+        coupling_max_files: usize,
+    ) -> Self {
+        Self {
+            top,
+            coupling_min_commits,
+            coupling_min_degree,
+            coupling_max_files,
+        }
+    }
+}
+
+/// Most changed paths and temporal coupling of a repository in a sprint.
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoHotspots {
+    pub repository: String,
+    pub files: Vec<PathChanges>,
+    /// A commit counts once for every directory above its files.
+    pub directories: Vec<PathChanges>,
+    pub coupling: Vec<CoupledFiles>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PathChanges {
+    pub path: String,
+    pub commits: usize,
+}
+
+/// Files that change together in the same commits.
+#[derive(Debug, Clone, Serialize)]
+pub struct CoupledFiles {
+    pub first: String,
+    pub second: String,
+    pub commits: usize,
+    /// Shared commits in percent of the average commits of the two files.
+    pub degree: usize,
+}
+
+pub type SprintsKnowledge = Vec<(Sprint, TeamKnowledge)>;
+
+/// Limits of the knowledge distribution analysis.
+#[derive(Debug, Clone, Copy)]
+pub struct KnowledgeThresholds {
+    /// Share of the lines of a module in percent one user has to exceed to be its only owner.
+    pub owner_share: usize,
+    /// Modules with fewer blamed lines are left out.
+    pub min_lines: usize,
+    /// Path components of the directory that make a module.
+    pub module_depth: usize,
+}
+
+impl KnowledgeThresholds {
+    pub fn new(owner_share: usize, min_lines: usize, module_depth: usize) -> Self {
+        Self {
+            owner_share,
+            min_lines,
+            module_depth,
+        }
+    }
+}
+
+/// Knowledge distribution of a team in a sprint.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TeamKnowledge {
+    /// Modules owned by one team member at the sprint end, the most lines first.
+    pub bus_factor: Vec<ModuleOwner>,
+    pub breadth: Vec<UserBreadth>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleOwner {
+    pub repository: String,
+    pub module: String,
+    /// Blamed lines of the module by any author.
+    pub lines: usize,
+    pub owner: String,
+    /// Lines of the owner in percent of the module lines.
+    pub share: usize,
+}
+
+/// Repositories and top-level modules a user committed to in a sprint.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserBreadth {
+    pub username: String,
+    pub repositories: Vec<String>,
+    /// `repository/module` of the first path component, `.` for root files.
+    pub modules: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_record, user, BASE_TIME};
+
+    #[test]
+    fn split_credit_divides_lines_and_files() {
+        let mut commit = commit_record("alice", BASE_TIME, &["a.rs", "b.rs", "c.rs"]);
+        commit.co_authors = vec!["bob@example.com".to_string()];
+        let totals = |username: &str, credit| {
+            let analyzed = CommitAnalyzed::from_commits(
+                vec![&commit],
+                &user(username, "core"),
+                credit,
+                &Language::builtin(),
+                &[],
+            );
+            (
+                analyzed.commits,
+                analyzed.files_changed,
+                analyzed.insertions,
+            )
+        };
+        // The author gets the remainder.
+        assert_eq!(totals("alice", CoAuthorCredit::Split), (1, 2, 2));
+        assert_eq!(totals("bob", CoAuthorCredit::Split), (1, 1, 1));
+        assert_eq!(totals("bob", CoAuthorCredit::Full), (1, 3, 3));
+    }
+}
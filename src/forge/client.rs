@@ -0,0 +1,42 @@
+use crate::forge::PullRequest;
+use crate::gitea::GiteaForge;
+use crate::github::GitHubForge;
+use crate::gitlab::GitLabForge;
+use crate::model::{BoxError, Repository};
+use chrono::{DateTime, FixedOffset};
+
+pub type Result<T> = std::result::Result<T, BoxError>;
+
+pub type PageProgress<'a> = Box<dyn FnMut(i64) + Send + 'a>;
+
+/// A code hosting service which pull requests and their reviews are fetched from.
+pub trait Forge {
+    /// Fetches pull requests created, merged or closed since `since` with all their reviews.
+    async fn fetch_pull_requests<'a>(
+        &self,
+        repo: &Repository,
+        since: &DateTime<FixedOffset>,
+        cb: PageProgress<'a>,
+    ) -> Result<Vec<PullRequest>>;
+}
+
+pub enum ForgeClient {
+    Gitea(GiteaForge),
+    GitHub(GitHubForge),
+    GitLab(GitLabForge),
+}
+
+impl Forge for ForgeClient {
+    async fn fetch_pull_requests<'a>(
+        &self,
+        repo: &Repository,
+        since: &DateTime<FixedOffset>,
+        cb: PageProgress<'a>,
+    ) -> Result<Vec<PullRequest>> {
+        match self {
+            ForgeClient::Gitea(forge) => forge.fetch_pull_requests(repo, since, cb).await,
+            ForgeClient::GitHub(forge) => forge.fetch_pull_requests(repo, since, cb).await,
+            ForgeClient::GitLab(forge) => forge.fetch_pull_requests(repo, since, cb).await,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+pub mod client;
+pub mod model;
+
+pub use client::Forge;
+pub use client::ForgeClient;
+pub use client::PageProgress;
+pub use client::Result;
+pub use model::ForgeUser;
+pub use model::PullRequest;
+pub use model::PullReview;
+pub use model::ReviewState;
@@ -0,0 +1,75 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForgeUser {
+    pub login: String,
+    pub email: Option<String>,
+}
+
+impl ForgeUser {
+    pub fn new(login: impl ToString, email: Option<impl ToString>) -> Self {
+        Self {
+            login: login.to_string(),
+            email: email.map(|e| e.to_string()).filter(|e| !e.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub number: i64,
+    pub author: ForgeUser,
+    pub created_at: DateTime<FixedOffset>,
+    pub merged_at: Option<DateTime<FixedOffset>>,
+    pub closed_at: Option<DateTime<FixedOffset>>,
+    pub reviews: Vec<PullReview>,
+}
+
+impl PullRequest {
+    pub fn new(
+        number: i64,
+        author: ForgeUser,
+        created_at: DateTime<FixedOffset>,
+        merged_at: Option<DateTime<FixedOffset>>,
+        closed_at: Option<DateTime<FixedOffset>>,
+        reviews: Vec<PullReview>,
+    ) -> Self {
+        Self {
+            number,
+            author,
+            created_at,
+            merged_at,
+            closed_at,
+            reviews,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullReview {
+    pub author: Option<ForgeUser>,
+    pub state: ReviewState,
+    pub comments_count: usize,
+}
+
+impl PullReview {
+    pub fn new(author: Option<ForgeUser>, state: ReviewState, comments_count: usize) -> Self {
+        Self {
+            author,
+            state,
+            comments_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReviewState {
+    Approved,
+    RequestChanges,
+    Comment,
+    Pending,
+    RequestReview,
+    Dismissed,
+    Unknown,
+}
@@ -0,0 +1,207 @@
+//! Rework of recently written lines, found by blaming the lines a commit changes in its first
+//! parent.
+
+use crate::git::mailmap::Mailmap;
+use crate::git::partial;
+use crate::git::path_filter::PathFilter;
+use crate::git::stats_cache::Churn;
+use git2::{BlameOptions, Diff, DiffFindOptions, Error, Oid, Patch, Repository};
+use std::collections::HashMap;
+use std::path::Path;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Author of a blamed line and its author time in seconds.
+pub struct LineOrigin {
+    pub name: String,
+    pub email: String,
+    pub time: i64,
+}
+
+/// Classifies the hunks of the first-parent `diff` of `commit`, a line written less than
+/// `days` days before the commit counts as recent.
+pub fn diff_churn(
+    repo: &Repository,
+    diff: &mut Diff<'_>,
+    commit: &git2::Commit<'_>,
+    paths: &PathFilter,
+    mailmap: &Mailmap,
+    days: u32,
+) -> Result<Churn, Error> {
+    let mut churn = Churn::default();
+    let Ok(parent_id) = commit.parent_id(0) else {
+        // A root commit changes nothing written before.
+        return Ok(churn);
+    };
+    let mut diff_find_options = DiffFindOptions::new();
+    diff_find_options.renames(true);
+    diff.find_similar(Some(&mut diff_find_options))?;
+    let recent_since = commit.author().when().seconds() - i64::from(days) * SECONDS_PER_DAY;
+    let is_partial = partial::is_partial(repo);
+    for (idx, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        if !paths.is_counted(path) {
+            continue;
+        }
+        let Some(patch) = Patch::from_diff(diff, idx)? else {
+            continue;
+        };
+        // Deleted old line numbers and the number of inserted lines per hunk.
+        let mut hunks = vec![];
+        for hunk_idx in 0..patch.num_hunks() {
+            let mut deleted = vec![];
+            let mut inserted = 0;
+            for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                match (line.origin(), line.old_lineno()) {
+                    ('-', Some(lineno)) => deleted.push(lineno as usize),
+                    ('+', _) => inserted += 1,
+                    _ => {}
+                }
+            }
+            hunks.push((deleted, inserted));
+        }
+        let deleted = hunks
+            .iter()
+            .flat_map(|(deleted, _)| deleted)
+            .copied()
+            .collect::<Vec<_>>();
+        let origins = match (delta.old_file().path(), deleted.is_empty()) {
+            (Some(old_path), false) if is_partial => {
+                partial::blame_lines(repo.path(), parent_id, old_path, &deleted)?
+            }
+            (Some(old_path), false) => blame_lines(repo, parent_id, old_path, &deleted)?,
+            _ => HashMap::new(),
+        };
+        for (deleted, inserted) in hunks {
+            let recent = deleted
+                .iter()
+                .filter_map(|lineno| origins.get(lineno))
+                .filter(|origin| origin.time >= recent_since)
+                .collect::<Vec<_>>();
+            if recent.is_empty() {
+                churn.new_work += inserted;
+            }
+            for origin in recent {
+                let email = mailmap.resolve_email(&origin.name, &origin.email);
+                *churn.rework.entry(email).or_default() += 1;
+            }
+        }
+    }
+    Ok(churn)
+}
+
+fn blame_lines(
+    repo: &Repository,
+    parent_id: Oid,
+    path: &Path,
+    lines: &[usize],
+) -> Result<HashMap<usize, LineOrigin>, Error> {
+    let (Some(min), Some(max)) = (lines.iter().min(), lines.iter().max()) else {
+        return Ok(HashMap::new());
+    };
+    let mut blame_options = BlameOptions::new();
+    blame_options
+        .newest_commit(parent_id)
+        .min_line(*min)
+        .max_line(*max);
+    let blame = repo.blame_file(path, Some(&mut blame_options))?;
+    Ok(lines
+        .iter()
+        .filter_map(|lineno| {
+            let hunk = blame.get_line(*lineno)?;
+            let signature = hunk.final_signature();
+            let origin = LineOrigin {
+                name: signature.name()?.to_string(),
+                email: signature.email()?.to_string(),
+                time: signature.when().seconds(),
+            };
+            Some((*lineno, origin))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{CommitQuery, CommitStatsCache, GitCommitRepository};
+    use crate::model::MergePolicy;
+    use crate::test_support::{commit, git_repository, query, BASE_TIME};
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    #[test]
+    fn splits_rework_by_author_within_window() {
+        let (dir, repo) = git_repository();
+        let old = "x1\nx2\n";
+        let first = commit(
+            &repo,
+            "main",
+            &[],
+            "alice",
+            BASE_TIME - 30 * DAY,
+            &[("old.txt", old)],
+        );
+        let own = "o1\no2\n";
+        let second = commit(
+            &repo,
+            "main",
+            &[first],
+            "alice",
+            BASE_TIME,
+            &[("old.txt", old), ("own.txt", own)],
+        );
+        let others = "p1\np2\n";
+        let third = commit(
+            &repo,
+            "main",
+            &[second],
+            "bob",
+            BASE_TIME + DAY,
+            &[("old.txt", old), ("own.txt", own), ("others.txt", others)],
+        );
+        commit(
+            &repo,
+            "main",
+            &[third],
+            "alice",
+            BASE_TIME + 2 * DAY,
+            &[
+                ("old.txt", "x1\nX2\nx3\n"),
+                ("own.txt", "o1\nO2\n"),
+                ("others.txt", "P1\np2\n"),
+            ],
+        );
+
+        let query = CommitQuery {
+            churn_days: Some(7),
+            ..query(MergePolicy::Full)
+        };
+        let mut cache = CommitStatsCache::open(dir.path().join("stats.json"), "");
+        let (mut commits, _) = repo.get_commits(&query, &mut cache).unwrap();
+        commits.sort_by_key(|c| c.datetime);
+        let churn = commits
+            .iter()
+            .map(|c| {
+                let churn = c.churn.clone().unwrap();
+                (churn.new_work, churn.rework.into_iter().collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        let rework = |email: &str| (email.to_string(), 1);
+        assert_eq!(
+            churn,
+            vec![
+                (2, vec![]),
+                (2, vec![]),
+                // `x2` was written before the window, so replacing it is new work. The lines
+                // inserted in place of `o2` and `p1` count in neither.
+                (
+                    2,
+                    vec![rework("alice@example.com"), rework("bob@example.com")]
+                ),
+            ]
+        );
+    }
+}
@@ -1,16 +1,51 @@
-use chrono::{DateTime, FixedOffset, Utc};
-use futures::pending;
-use git2::{DiffFindOptions, DiffFormat, DiffOptions, DiffStats, Error, Repository};
-use std::env::home_dir;
+use crate::git::churn;
+use crate::git::mailmap::Mailmap;
+use crate::git::partial;
+use crate::git::path_filter::PathFilter;
+use crate::git::stats_cache::{Churn, CommitStats, CommitStatsCache, LineStats};
+use crate::model::MergePolicy;
+use chrono::{DateTime, FixedOffset};
+use clap::ValueEnum;
+use git2::{
+    Diff, DiffFindOptions, DiffOptions, Error, IndexEntry, MergeFileOptions, Oid, Patch,
+    Repository, Sort,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
 
-#[derive(Debug, Clone)]
+/// Describes the options of `get_commit_stats_for_commit`, change it with them to invalidate
+/// cached stats.
+pub const DIFF_FINGERPRINT: &str = "v3;patience;untracked;typechange;ignored;renames";
+/// Same for `partial::commit_stats` of partial clones.
+pub const PARTIAL_DIFF_FINGERPRINT: &str = "v3;git-cli;patience;renames";
+
+const CO_AUTHORED_BY: &str = "Co-authored-by";
+
+/// Mask of the conflict stage bits of `IndexEntry::flags`.
+const INDEX_STAGE_MASK: u16 = 0x3000;
+
+/// Length of the conflict markers of a re-merge, longer than the usual 7 so that lines of the
+/// files looking like markers aren't taken for them.
+const CONFLICT_MARKER_SIZE: u16 = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Commit {
     pub email: String,
     pub message: String,
     pub files_changed: usize,
     pub insertions: usize,
     pub deletions: usize,
-    pub datetime: DateTime<Utc>,
+    /// Lines per file type.
+    pub extensions: BTreeMap<String, LineStats>,
+    /// Counted files.
+    pub files: Vec<String>,
+    /// Emails of the `Co-authored-by` trailers other than the author.
+    pub co_authors: Vec<String>,
+    /// Absent unless the churn window was set.
+    pub churn: Option<Churn>,
+    /// Author or committer time per `CommitDate`, in the offset it was recorded with.
+    pub datetime: DateTime<FixedOffset>,
 }
 
 impl Commit {
@@ -20,7 +55,8 @@ impl Commit {
         files_changed: usize,
         insertions: usize,
         deletions: usize,
-        datetime: DateTime<Utc>,
+        extensions: BTreeMap<String, LineStats>,
+        datetime: DateTime<FixedOffset>,
     ) -> Self {
         Self {
             email: email.to_string(),
@@ -28,90 +64,582 @@ impl Commit {
             files_changed,
             insertions,
             deletions,
+            extensions,
+            files: vec![],
+            co_authors: vec![],
+            churn: None,
             datetime,
         }
     }
 }
 
+/// Which time of a commit places it in a sprint.
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CommitDate {
+    /// When the change was written, kept by rebases and cherry-picks.
+    Author,
+    /// When the commit was created, a rebase moves it to the rebase time.
+    Committer,
+}
+
+/// Which commits `get_commits` keeps.
+#[derive(Debug, Clone)]
+pub struct CommitQuery {
+    /// Branches to walk, `*` matches any suffix.
+    pub branches: Vec<String>,
+    pub since: DateTime<FixedOffset>,
+    pub until: DateTime<FixedOffset>,
+    /// The walk stops after this many consecutive commits older than `since`, `0` walks the
+    /// whole history.
+    pub stop_after: usize,
+    pub merges: MergePolicy,
+    pub date: CommitDate,
+    /// Lines changed again within this many days after they were written count as rework,
+    /// `None` skips the churn analysis.
+    pub churn_days: Option<u32>,
+    /// `.gitignore` style patterns of files to count, empty counts every file.
+    pub include: Vec<String>,
+    /// `.gitignore` style patterns of files not to count.
+    pub exclude: Vec<String>,
+}
+
+impl CommitQuery {
+    pub fn new(
+        branches: Vec<String>,
+        since: DateTime<FixedOffset>,
+        until: DateTime<FixedOffset>,
+        stop_after: usize,
+        merges: MergePolicy,
+        include: Vec<String>,
+        exclude: Vec<String>,
+    ) -> Self {
+        Self {
+            branches,
+            since,
+            until,
+            stop_after,
+            merges,
+            date: CommitDate::Author,
+            churn_days: None,
+            include,
+            exclude,
+        }
+    }
+}
+
 pub trait GitCommitRepository {
-    fn get_commits(&self, since: &DateTime<FixedOffset>) -> Result<Vec<Commit>, Error>;
+    fn diff_fingerprint(&self, query: &CommitQuery) -> Result<String, Error>;
+    /// Returns the kept commits and the number of scanned ones.
+    fn get_commits(
+        &self,
+        query: &CommitQuery,
+        cache: &mut CommitStatsCache,
+    ) -> Result<(Vec<Commit>, usize), Error>;
 }
 
 impl GitCommitRepository for Repository {
-    fn get_commits(&self, since: &DateTime<FixedOffset>) -> Result<Vec<Commit>, Error> {
-        let git_commits = get_commits(self, since)?;
+    fn diff_fingerprint(&self, query: &CommitQuery) -> Result<String, Error> {
+        let fingerprint = if partial::is_partial(self) {
+            PARTIAL_DIFF_FINGERPRINT
+        } else {
+            DIFF_FINGERPRINT
+        };
+        // Only `Remerge` diffs merge commits differently from other commits.
+        let merges = match query.merges {
+            MergePolicy::Remerge => ";remerge=v3",
+            MergePolicy::Full | MergePolicy::Skip | MergePolicy::FirstParent => "",
+        };
+        // Rework is cached by mailmapped email, a changed `.mailmap` attributes it anew.
+        let churn = match query.churn_days {
+            Some(days) => {
+                let mailmap = root_file_id(self, query, ".mailmap")?
+                    .map_or("none".to_string(), |id| id.to_string());
+                format!(";churn={days};mailmap={mailmap}")
+            }
+            None => String::new(),
+        };
+        let paths = path_filter(self, query)?;
+        Ok(format!("{fingerprint}{merges}{churn};{}", paths.fingerprint()))
+    }
+
+    fn get_commits(
+        &self,
+        query: &CommitQuery,
+        cache: &mut CommitStatsCache,
+    ) -> Result<(Vec<Commit>, usize), Error> {
+        let (git_commits, scanned) = get_commits(self, query)?;
+        let is_partial = partial::is_partial(self);
+        let paths = path_filter(self, query)?;
+        let mailmap = mailmap(self, query)?;
         let commits = git_commits
             .iter()
             .map(|git_commit| {
-                let git_diff = get_commit_stats_for_commit(self, &git_commit).unwrap();
-                git_commit_to_commit(&git_commit, &git_diff).unwrap()
+                let stats = match cache.get(&git_commit.id()) {
+                    Some(stats) => stats,
+                    None => {
+                        let remerge =
+                            query.merges == MergePolicy::Remerge && git_commit.parent_count() > 1;
+                        let mut stats = match (is_partial, remerge) {
+                            (false, false) => {
+                                get_commit_stats_for_commit(self, git_commit, &paths)?
+                            }
+                            (false, true) => get_merge_stats_for_commit(self, git_commit, &paths)?,
+                            (true, false) => {
+                                partial::commit_stats(self.path(), git_commit, &paths)?
+                            }
+                            (true, true) => {
+                                partial::fetch_merge_blobs(self.path(), git_commit)?;
+                                get_merge_stats_for_commit(self, git_commit, &paths)?
+                            }
+                        };
+                        if let Some(days) = query.churn_days {
+                            if git_commit.parent_count() <= 1 {
+                                if is_partial {
+                                    partial::fetch_commit_blobs(self.path(), git_commit)?;
+                                }
+                                let mut diff = get_diff_for_commit(self, git_commit)?;
+                                stats.churn = Some(churn::diff_churn(
+                                    self,
+                                    &mut diff,
+                                    git_commit,
+                                    &paths,
+                                    &mailmap,
+                                    days,
+                                )?);
+                            }
+                        }
+                        cache.insert(&git_commit.id(), stats.clone());
+                        stats
+                    }
+                };
+                git_commit_to_commit(git_commit, &stats, &mailmap, query.date)
             })
-            .collect::<Vec<_>>();
-        Ok(commits)
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok((commits, scanned))
     }
 }
 
+/// Patterns of the query and the root `.gitattributes` of the configured branch.
+pub fn path_filter(repo: &Repository, query: &CommitQuery) -> Result<PathFilter, Error> {
+    let gitattributes = read_root_file(repo, query, ".gitattributes")?;
+    PathFilter::new(&query.include, &query.exclude, gitattributes.as_deref())
+}
+
+/// The root `.mailmap` of the configured branch, so it applies to every walked branch.
+pub fn mailmap(repo: &Repository, query: &CommitQuery) -> Result<Mailmap, Error> {
+    let mailmap = read_root_file(repo, query, ".mailmap")?;
+    Ok(Mailmap::from_buffer(mailmap.as_deref().unwrap_or_default()))
+}
+
+fn read_root_file(
+    repo: &Repository,
+    query: &CommitQuery,
+    name: &str,
+) -> Result<Option<String>, Error> {
+    let Some(id) = root_file_id(repo, query, name)? else {
+        return Ok(None);
+    };
+    let content = match repo.find_blob(id) {
+        Ok(blob) => String::from_utf8_lossy(blob.content()).into_owned(),
+        Err(_) if partial::is_partial(repo) => partial::read_blob(repo.path(), id)?,
+        Err(err) => return Err(err),
+    };
+    Ok(Some(content))
+}
+
+/// Blob id of a file in the root of the configured branch.
+fn root_file_id(repo: &Repository, query: &CommitQuery, name: &str) -> Result<Option<Oid>, Error> {
+    let Some(branch) = query.branches.first() else {
+        return Ok(None);
+    };
+    let tree = repo
+        .find_reference(&format!("refs/heads/{branch}"))?
+        .peel_to_tree()?;
+    Ok(tree.get_name(name).map(|entry| entry.id()))
+}
+
 fn get_commits<'a>(
     repo: &'a Repository,
-    since: &'a DateTime<FixedOffset>,
-) -> Result<Vec<git2::Commit<'a>>, Error> {
+    query: &CommitQuery,
+) -> Result<(Vec<git2::Commit<'a>>, usize), Error> {
+    // The local branches are kept equal to the remote ones in every cache mode, a bare cache
+    // has no meaningful `HEAD` to walk from. A commit reachable from several branches is
+    // walked once.
     let mut revwalk = repo.revwalk()?;
-    revwalk.push_head()?;
-    let commits = revwalk
-        .filter_map(|id| repo.find_commit(id.ok()?).ok())
-        .filter(|commit| {
-            let datetime = DateTime::<Utc>::from_utc(
-                chrono::NaiveDateTime::from_timestamp_opt(commit.time().seconds(), 0).unwrap(),
-                Utc,
-            );
-            datetime >= *since
-        })
-        .collect();
-    Ok(commits)
+    revwalk.set_sorting(Sort::TIME)?;
+    if query.merges == MergePolicy::FirstParent {
+        revwalk.simplify_first_parent()?;
+    }
+    for branch in &query.branches {
+        if branch.contains('*') {
+            revwalk.push_glob(&format!("refs/heads/{branch}"))?;
+        } else {
+            revwalk.push_ref(&format!("refs/heads/{branch}"))?;
+        }
+    }
+
+    // Newest commits come first, but a commit with a skewed clock or an old branch merged late
+    // can still be followed by newer ones, so the walk only stops after a run of old commits.
+    // The walk is ordered by committer time, so only it can tell when to stop.
+    let mut commits = vec![];
+    let mut scanned = 0;
+    let mut old_in_row = 0;
+    for id in revwalk {
+        let commit = repo.find_commit(id?)?;
+        scanned += 1;
+        let Some(committed) = commit_datetime(&commit, CommitDate::Committer) else {
+            continue;
+        };
+        if committed < query.since {
+            old_in_row += 1;
+            if old_in_row == query.stop_after {
+                break;
+            }
+            continue;
+        }
+        old_in_row = 0;
+        let Some(datetime) = commit_datetime(&commit, query.date) else {
+            continue;
+        };
+        let skipped = query.merges == MergePolicy::Skip && commit.parent_count() > 1;
+        if datetime >= query.since && datetime <= query.until && !skipped {
+            commits.push(commit);
+        }
+    }
+    Ok((commits, scanned))
 }
 
 fn get_commit_stats_for_commit<'a>(
     repo: &'a Repository,
     commit: &git2::Commit<'a>,
-) -> Result<DiffStats, Error> {
-    let mut diff_options = DiffOptions::new();
-    diff_options.patience(true);
-    diff_options.include_untracked(true);
-    diff_options.include_typechange(true);
-    diff_options.include_ignored(true);
-    let mut diff_find_options = DiffFindOptions::new();
-    diff_find_options.renames(true);
+    paths: &PathFilter,
+) -> Result<CommitStats, Error> {
+    let mut diff = get_diff_for_commit(repo, commit)?;
+    get_diff_stats(&mut diff, paths)
+}
+
+/// Diff against the first parent, or against the empty tree for a root commit.
+fn get_diff_for_commit<'a>(
+    repo: &'a Repository,
+    commit: &git2::Commit<'a>,
+) -> Result<Diff<'a>, Error> {
     let old_tree = if commit.parent_count() > 0 {
         let parent = commit.parent(0)?;
         Some(parent.tree()?)
     } else {
         None
     };
-    let mut diff = repo.diff_tree_to_tree(
+    repo.diff_tree_to_tree(
         old_tree.as_ref(),
         Some(&commit.tree()?),
-        Some(&mut diff_options),
-    )?;
+        Some(&mut diff_options()),
+    )
+}
+
+/// Diffs a merge commit against the automatic merge of its parents, so only the changes made
+/// by whoever merged are counted, like `git show --remerge-diff`. Conflicted files are merged
+/// with both sides of each conflict and without its markers, so the resolution counts as the
+/// lines it inserts and the lines of the sides it drops.
+fn get_merge_stats_for_commit<'a>(
+    repo: &'a Repository,
+    commit: &git2::Commit<'a>,
+    paths: &PathFilter,
+) -> Result<CommitStats, Error> {
+    // Octopus merges can't have conflicts, there is nothing of their own to count.
+    if commit.parent_count() != 2 {
+        return Ok(CommitStats::default());
+    }
+    let mut index = repo.merge_commits(&commit.parent(0)?, &commit.parent(1)?, None)?;
+    if index.has_conflicts() {
+        let conflicts = index.conflicts()?.collect::<Result<Vec<_>, Error>>()?;
+        for conflict in conflicts {
+            let Some(entry) = conflict
+                .our
+                .as_ref()
+                .or(conflict.their.as_ref())
+                .or(conflict.ancestor.as_ref())
+            else {
+                continue;
+            };
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            index.remove_path(Path::new(&path))?;
+            match (&conflict.ancestor, &conflict.our, &conflict.their) {
+                (ancestor, Some(our), Some(their)) => {
+                    // Both sides adding the file merge against an empty one.
+                    let empty;
+                    let ancestor = match ancestor {
+                        Some(ancestor) => ancestor,
+                        None => {
+                            empty = entry_with_blob(our, repo.blob(&[])?, 0);
+                            &empty
+                        }
+                    };
+                    let mut options = MergeFileOptions::new();
+                    options.marker_size(CONFLICT_MARKER_SIZE);
+                    let merged =
+                        repo.merge_file_from_index(ancestor, our, their, Some(&mut options))?;
+                    let content = strip_conflict_markers(merged.content());
+                    let id = repo.blob(&content)?;
+                    index.add(&entry_with_blob(our, id, content.len()))?;
+                }
+                // A file deleted on one side is taken as the first parent has it.
+                (_, Some(our), None) => {
+                    index.add(&entry_with_blob(our, our.id, our.file_size as usize))?;
+                }
+                (_, None, _) => {}
+            }
+        }
+    }
+    // The merged index isn't written as a tree, a partial clone lacks the blobs of unchanged
+    // files which writing checks for. Diffing it reversed gives the merge-to-commit direction.
+    let mut diff_options = diff_options();
+    diff_options.reverse(true);
+    let mut diff =
+        repo.diff_tree_to_index(Some(&commit.tree()?), Some(&index), Some(&mut diff_options))?;
+    get_diff_stats(&mut diff, paths)
+}
+
+/// Lines of a merged file other than the `CONFLICT_MARKER_SIZE` long conflict markers.
+fn strip_conflict_markers(content: &[u8]) -> Vec<u8> {
+    let size = CONFLICT_MARKER_SIZE as usize;
+    let is_marker = |line: &[u8]| {
+        [b'<', b'=', b'>'].iter().any(|marker| {
+            line.len() >= size
+                && line[..size].iter().all(|c| c == marker)
+                && line.get(size).is_none_or(|c| c.is_ascii_whitespace())
+        })
+    };
+    content
+        .split_inclusive(|c| *c == b'\n')
+        .filter(|line| !is_marker(line))
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/// Stage 0 copy of a conflict entry with another blob.
+fn entry_with_blob(entry: &IndexEntry, id: Oid, size: usize) -> IndexEntry {
+    IndexEntry {
+        ctime: entry.ctime,
+        mtime: entry.mtime,
+        dev: entry.dev,
+        ino: entry.ino,
+        mode: entry.mode,
+        uid: entry.uid,
+        gid: entry.gid,
+        file_size: size as u32,
+        id,
+        flags: entry.flags & !INDEX_STAGE_MASK,
+        flags_extended: entry.flags_extended,
+        path: entry.path.clone(),
+    }
+}
+
+fn diff_options() -> DiffOptions {
+    let mut diff_options = DiffOptions::new();
+    diff_options.patience(true);
+    diff_options.include_untracked(true);
+    diff_options.include_typechange(true);
+    diff_options.include_ignored(true);
+    diff_options
+}
+
+/// Sums the line stats of the files `paths` counts, renames are detected before filtering.
+fn get_diff_stats(diff: &mut Diff<'_>, paths: &PathFilter) -> Result<CommitStats, Error> {
+    let mut diff_find_options = DiffFindOptions::new();
+    diff_find_options.renames(true);
     diff.find_similar(Some(&mut diff_find_options))?;
-    diff.stats().map_err(Into::into)
+    let mut stats = CommitStats::default();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        if !paths.is_counted(path) {
+            continue;
+        }
+        // Binary files have no patch lines, they count as a changed file only.
+        let (insertions, deletions) = match Patch::from_diff(diff, idx)? {
+            Some(patch) => {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                (insertions, deletions)
+            }
+            None => (0, 0),
+        };
+        stats.add_file(path, insertions, deletions);
+    }
+    Ok(stats)
 }
 
 fn git_commit_to_commit(
     git_commit: &git2::Commit<'_>,
-    git_diff: &DiffStats,
+    stats: &CommitStats,
+    mailmap: &Mailmap,
+    date: CommitDate,
 ) -> Result<Commit, Error> {
     let author = git_commit.author();
-    let message = git_commit.message();
-    let datetime = DateTime::<Utc>::from_utc(
-        chrono::NaiveDateTime::from_timestamp_opt(git_commit.time().seconds(), 0).unwrap(),
-        Utc,
+    let email = mailmap.resolve_email(
+        author.name().unwrap_or(""),
+        author.email().unwrap_or(""),
     );
-    Ok(Commit::new(
-        author.email().unwrap_or("").to_string(),
-        message.unwrap_or("").to_string(),
-        git_diff.files_changed(),
-        git_diff.insertions(),
-        git_diff.deletions(),
-        datetime,
-    ))
+    let email = email.as_str();
+    let message = git_commit.message().unwrap_or("");
+    let Some(datetime) = commit_datetime(git_commit, date) else {
+        return Err(Error::from_str("Not a valid commit time"));
+    };
+    Ok(Commit {
+        files: stats.files.clone(),
+        co_authors: co_authors(message, email, mailmap),
+        churn: stats.churn.clone(),
+        ..Commit::new(
+            email,
+            message,
+            stats.files_changed,
+            stats.insertions,
+            stats.deletions,
+            stats.extensions.clone(),
+            datetime,
+        )
+    })
+}
+
+/// Emails of the `Co-authored-by: Name <email>` trailers resolved through the mailmap, without
+/// duplicates and the author. The name may be empty, trailers without an email are skipped.
+fn co_authors(message: &str, author: &str, mailmap: &Mailmap) -> Vec<String> {
+    let mut co_authors: Vec<String> = vec![];
+    for line in message.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !key.trim().eq_ignore_ascii_case(CO_AUTHORED_BY) {
+            continue;
+        }
+        let Some((name, email)) = value.trim().strip_suffix('>').and_then(|v| v.split_once('<'))
+        else {
+            continue;
+        };
+        let email = mailmap.resolve_email(name.trim(), email.trim());
+        let is_known = email.eq_ignore_ascii_case(author)
+            || co_authors.iter().any(|e| e.eq_ignore_ascii_case(&email));
+        if !email.is_empty() && !is_known {
+            co_authors.push(email);
+        }
+    }
+    co_authors
+}
+
+fn commit_datetime(commit: &git2::Commit<'_>, date: CommitDate) -> Option<DateTime<FixedOffset>> {
+    let time = match date {
+        CommitDate::Author => commit.author().when(),
+        CommitDate::Committer => commit.time(),
+    };
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60)?;
+    Some(DateTime::from_timestamp(time.seconds(), 0)?.with_timezone(&offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit, git_repository, query, BASE_TIME};
+    use tempfile::TempDir;
+
+    /// `main` and `feature` change the first line of `a.txt` differently, the merge resolves
+    /// the conflict by combining them, and brings `b.txt` of `feature`.
+    fn merged_repository() -> (TempDir, Repository) {
+        let (dir, repo) = git_repository();
+        let base = commit(
+            &repo,
+            "main",
+            &[],
+            "alice",
+            BASE_TIME,
+            &[("a.txt", "1\n2\n3\n4\n5\n")],
+        );
+        let feature = commit(
+            &repo,
+            "feature",
+            &[base],
+            "alice",
+            BASE_TIME + 60,
+            &[("a.txt", "one\n2\n3\n4\n5\n"), ("b.txt", "b\nb\n")],
+        );
+        let main = commit(
+            &repo,
+            "main",
+            &[base],
+            "alice",
+            BASE_TIME + 120,
+            &[("a.txt", "uno\n2\n3\n4\n5\n")],
+        );
+        commit(
+            &repo,
+            "main",
+            &[main, feature],
+            "alice",
+            BASE_TIME + 180,
+            &[("a.txt", "one uno\n2\n3\n4\n5\n"), ("b.txt", "b\nb\n")],
+        );
+        (dir, repo)
+    }
+
+    /// Commits, inserted and deleted lines of `main` under `merges`.
+    fn totals(merges: MergePolicy) -> (usize, usize, usize) {
+        let (dir, repo) = merged_repository();
+        let query = query(merges);
+        let mut cache = CommitStatsCache::open(dir.path().join("stats.json"), "");
+        let (commits, _) = repo.get_commits(&query, &mut cache).unwrap();
+        let insertions = commits.iter().map(|c| c.insertions).sum();
+        let deletions = commits.iter().map(|c| c.deletions).sum();
+        (commits.len(), insertions, deletions)
+    }
+
+    #[test]
+    fn full_counts_merges_against_first_parent() {
+        // The merge adds `b.txt` and changes `a.txt` against `main`.
+        assert_eq!(totals(MergePolicy::Full), (4, 5 + 3 + 1 + 3, 1 + 1 + 1));
+    }
+
+    #[test]
+    fn skip_leaves_merges_out() {
+        assert_eq!(totals(MergePolicy::Skip), (3, 5 + 3 + 1, 1 + 1));
+    }
+
+    #[test]
+    fn first_parent_leaves_merged_commits_out() {
+        assert_eq!(totals(MergePolicy::FirstParent), (3, 5 + 1 + 3, 1 + 1));
+    }
+
+    #[test]
+    fn remerge_counts_only_conflict_resolution() {
+        // The resolution replaces both sides of the conflict with one line.
+        assert_eq!(totals(MergePolicy::Remerge), (4, 5 + 3 + 1 + 1, 1 + 1 + 2));
+    }
+
+    #[test]
+    fn strips_only_merge_conflict_markers() {
+        let marker = |c: &str| c.repeat(CONFLICT_MARKER_SIZE as usize);
+        let merged = format!(
+            "Title\n=======\n{} ours\nuno\n{}\none\n{} theirs\nend",
+            marker("<"),
+            marker("="),
+            marker(">")
+        );
+        assert_eq!(
+            strip_conflict_markers(merged.as_bytes()),
+            b"Title\n=======\nuno\none\nend"
+        );
+    }
+
+    #[test]
+    fn co_authors_resolve_through_mailmap_without_name() {
+        let mailmap = Mailmap::from_buffer("Bob <bob@example.com> <bob@old.example.com>\n");
+        let message = "Fix\n\nCo-authored-by: <bob@old.example.com>\n\
+            Co-authored-by: Carol <carol@example.com>\n\
+            co-authored-by: Bob <BOB@example.com>\n\
+            Co-authored-by: Alice <alice@example.com>\n\
+            Co-authored-by: Nobody\n";
+        assert_eq!(
+            co_authors(message, "alice@example.com", &mailmap),
+            vec!["bob@example.com", "carol@example.com"]
+        );
+    }
 }
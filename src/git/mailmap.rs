@@ -0,0 +1,110 @@
+//! `.mailmap` lookup of author emails. `git2::Mailmap` only resolves a `Signature`, which can't
+//! have an empty name, and `Co-authored-by` trailers may come without one.
+
+/// Entries of a `.mailmap` in the forms `git check-mailmap` reads.
+#[derive(Debug, Default)]
+pub struct Mailmap {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    proper_email: Option<String>,
+    commit_name: Option<String>,
+    commit_email: String,
+}
+
+// Create
+impl Mailmap {
+    pub fn from_buffer(buffer: &str) -> Self {
+        Self {
+            entries: buffer.lines().filter_map(parse_line).collect(),
+        }
+    }
+}
+
+// Matching
+impl Mailmap {
+    /// Email of `name <email>` after the mailmap, an entry with the commit name wins over one
+    /// without and a later entry over an earlier one, as in git. Names and emails match
+    /// regardless of case.
+    pub fn resolve_email(&self, name: &str, email: &str) -> String {
+        let matching = |with_name: bool| {
+            self.entries.iter().rev().find(|entry| {
+                entry.commit_email.eq_ignore_ascii_case(email)
+                    && match &entry.commit_name {
+                        Some(commit_name) => with_name && commit_name.eq_ignore_ascii_case(name),
+                        None => !with_name,
+                    }
+            })
+        };
+        matching(true)
+            .or_else(|| matching(false))
+            .and_then(|entry| entry.proper_email.clone())
+            .unwrap_or_else(|| email.to_string())
+    }
+}
+
+// Parser
+/// Reads `Name <email>`, `<proper> <commit>`, `Name <proper> <commit>` and
+/// `Name <proper> Commit Name <commit>` lines, comments start with `#`.
+fn parse_line(line: &str) -> Option<Entry> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    let (_, rest) = line.split_once('<')?;
+    let (first_email, rest) = rest.split_once('>')?;
+    let first_email = first_email.trim();
+    let entry = match rest.split_once('<') {
+        Some((commit_name, rest)) => {
+            let (commit_email, _) = rest.split_once('>')?;
+            let commit_name = commit_name.trim();
+            Entry {
+                proper_email: (!first_email.is_empty()).then(|| first_email.to_string()),
+                commit_name: (!commit_name.is_empty()).then(|| commit_name.to_string()),
+                commit_email: commit_email.trim().to_string(),
+            }
+        }
+        // Only the name of the commit email is replaced.
+        None => Entry {
+            proper_email: None,
+            commit_name: None,
+            commit_email: first_email.to_string(),
+        },
+    };
+    Some(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAILMAP: &str = "\
+# Team
+Alice <alice@example.com>
+<bob@example.com> <bob@old.example.com>
+Carol <carol@example.com> <CAROL@laptop>
+Dave <dave@example.com> Dave Work <shared@example.com>
+Erin <erin@example.com> <shared@example.com>
+";
+
+    #[test]
+    fn resolves_every_form() {
+        let mailmap = Mailmap::from_buffer(MAILMAP);
+        let resolve = |name, email| mailmap.resolve_email(name, email);
+        assert_eq!(resolve("Alice", "alice@example.com"), "alice@example.com");
+        assert_eq!(resolve("Bob", "bob@old.example.com"), "bob@example.com");
+        assert_eq!(resolve("Carol", "carol@laptop"), "carol@example.com");
+        assert_eq!(resolve("dave work", "shared@example.com"), "dave@example.com");
+        assert_eq!(resolve("Someone", "shared@example.com"), "erin@example.com");
+        assert_eq!(resolve("Frank", "frank@example.com"), "frank@example.com");
+    }
+
+    #[test]
+    fn resolves_without_name() {
+        let mailmap = Mailmap::from_buffer(MAILMAP);
+        assert_eq!(mailmap.resolve_email("", "bob@old.example.com"), "bob@example.com");
+        assert_eq!(mailmap.resolve_email("", "shared@example.com"), "erin@example.com");
+    }
+}
@@ -1,7 +1,19 @@
+pub mod churn;
 pub mod commit;
+pub mod mailmap;
+pub mod ownership;
+pub mod partial;
+pub mod path_filter;
 pub mod repository;
+pub mod stats_cache;
 
 pub use commit::Commit;
+pub use commit::CommitDate;
+pub use commit::CommitQuery;
 pub use commit::GitCommitRepository;
+pub use ownership::GitOwnershipRepository;
+pub use ownership::Ownership;
+pub use repository::CacheMode;
 pub use repository::GitRepository;
-pub use repository::PercentProgress;
+pub use stats_cache::CommitStatsCache;
+pub use stats_cache::LineStats;
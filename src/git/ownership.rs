@@ -0,0 +1,142 @@
+//! Authors of the lines in the tree of the configured branch, found by blaming every file.
+
+use crate::git::churn::LineOrigin;
+use crate::git::commit::{mailmap, path_filter};
+use crate::git::partial;
+use crate::git::CommitQuery;
+use chrono::{DateTime, FixedOffset};
+use git2::{BlameOptions, Error, ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Mode of symbolic links in trees.
+const FILEMODE_LINK: i32 = 0o120000;
+
+/// Blamed lines of the tree at a moment, per directory and author email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ownership {
+    pub at: DateTime<FixedOffset>,
+    /// Last commit of the configured branch committed by `at`.
+    pub commit: String,
+    /// Lines per parent directory of the files, `.` for the root, and mailmapped email.
+    pub directories: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+pub trait GitOwnershipRepository {
+    /// Ownership of the configured branch at each moment, moments before its first commit are
+    /// left out.
+    fn get_ownership(
+        &self,
+        query: &CommitQuery,
+        at: &[DateTime<FixedOffset>],
+    ) -> Result<Vec<Ownership>, Error>;
+}
+
+impl GitOwnershipRepository for Repository {
+    fn get_ownership(
+        &self,
+        query: &CommitQuery,
+        at: &[DateTime<FixedOffset>],
+    ) -> Result<Vec<Ownership>, Error> {
+        let Some(branch) = query.branches.first() else {
+            return Ok(vec![]);
+        };
+        let paths = path_filter(self, query)?;
+        let mailmap = mailmap(self, query)?;
+        let is_partial = partial::is_partial(self);
+        let tip = self
+            .find_reference(&format!("refs/heads/{branch}"))?
+            .peel_to_commit()?;
+        // An unchanged blob has the same blame at a later commit, sprints mostly share files.
+        let mut blamed: HashMap<(PathBuf, Oid), HashMap<String, usize>> = HashMap::new();
+        let mut result = vec![];
+        for at in at {
+            let Some(commit) = last_commit_by(&tip, at) else {
+                continue;
+            };
+            let mut files = vec![];
+            commit.tree()?.walk(TreeWalkMode::PreOrder, |root, entry| {
+                if entry.kind() == Some(ObjectType::Blob) && entry.filemode() != FILEMODE_LINK {
+                    let path = Path::new(root).join(entry.name().unwrap_or_default());
+                    files.push((path, entry.id()));
+                }
+                TreeWalkResult::Ok
+            })?;
+            let mut directories: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+            for (path, blob_id) in files {
+                if !paths.is_counted(&path) {
+                    continue;
+                }
+                let key = (path, blob_id);
+                if !blamed.contains_key(&key) {
+                    let origins = if is_partial {
+                        partial::blame_file(self.path(), commit.id(), &key.0)?
+                    } else {
+                        blame_file(self, commit.id(), &key.0)?
+                    };
+                    // Partial clones have the blob after the blame fetched it.
+                    let is_binary = self.find_blob(blob_id).is_ok_and(|blob| blob.is_binary());
+                    let mut lines: HashMap<String, usize> = HashMap::new();
+                    if !is_binary {
+                        for origin in &origins {
+                            let email = mailmap.resolve_email(&origin.name, &origin.email);
+                            *lines.entry(email).or_default() += 1;
+                        }
+                    }
+                    blamed.insert(key.clone(), lines);
+                }
+                let directory = directories.entry(directory(&key.0)).or_default();
+                for (email, lines) in &blamed[&key] {
+                    *directory.entry(email.clone()).or_default() += lines;
+                }
+            }
+            result.push(Ownership {
+                at: *at,
+                commit: commit.id().to_string(),
+                directories,
+            });
+        }
+        Ok(result)
+    }
+}
+
+/// First-parent ancestor of `tip` committed last by `at`, the branch as it was then.
+fn last_commit_by<'a>(
+    tip: &git2::Commit<'a>,
+    at: &DateTime<FixedOffset>,
+) -> Option<git2::Commit<'a>> {
+    let mut commit = tip.clone();
+    while commit.committer().when().seconds() > at.timestamp() {
+        commit = commit.parent(0).ok()?;
+    }
+    Some(commit)
+}
+
+fn blame_file(repo: &Repository, commit: Oid, path: &Path) -> Result<Vec<LineOrigin>, Error> {
+    let mut blame_options = BlameOptions::new();
+    blame_options.newest_commit(commit);
+    let blame = repo.blame_file(path, Some(&mut blame_options))?;
+    let mut origins = vec![];
+    for hunk in blame.iter() {
+        let signature = hunk.final_signature();
+        let (Some(name), Some(email)) = (signature.name(), signature.email()) else {
+            continue;
+        };
+        for _ in 0..hunk.lines_in_hunk() {
+            origins.push(LineOrigin {
+                name: name.to_string(),
+                email: email.to_string(),
+                time: signature.when().seconds(),
+            });
+        }
+    }
+    Ok(origins)
+}
+
+fn directory(path: &Path) -> String {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().into_owned(),
+        _ => ".".to_string(),
+    }
+}
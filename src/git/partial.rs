@@ -0,0 +1,259 @@
+//! Blobless partial clones through the `git` command line, `libgit2` can neither clone with
+//! a filter nor fetch missing blobs on demand.
+
+use crate::git::churn::LineOrigin;
+use crate::git::path_filter::PathFilter;
+use crate::git::repository::CacheMode;
+use crate::git::stats_cache::CommitStats;
+use git2::{Error, ErrorClass, ErrorCode, Oid, Repository};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::{Command, Output};
+
+const FILTER: &str = "--filter=blob:none";
+
+/// Whether the repository was cloned with a filter and may miss objects.
+pub fn is_partial(repo: &Repository) -> bool {
+    repo.config()
+        .and_then(|config| config.get_string("remote.origin.partialclonefilter"))
+        .is_ok()
+}
+
+/// Why a partial clone of `url` can't be made, `None` when it can.
+pub fn unsupported_reason(url: &str) -> Result<Option<String>, Error> {
+    if Command::new("git").arg("--version").output().is_err() {
+        return Ok(Some("`git` can't be run".to_string()));
+    }
+    if !supports_filter(url)? {
+        return Ok(Some("the server doesn't support filters".to_string()));
+    }
+    Ok(None)
+}
+
+/// Whether the server advertises the `filter` capability. `git ls-remote` only prints it in the
+/// packet trace, protocol v2 lists it with `fetch=` and v0 after the first ref.
+fn supports_filter(url: &str) -> Result<bool, Error> {
+    let mut command = Command::new("git");
+    command
+        .args(["ls-remote", url, "HEAD"])
+        .env("GIT_TRACE_PACKET", "1");
+    let output = run(&mut command, "ls-remote")?;
+    let trace = String::from_utf8_lossy(&output.stderr);
+    let supports = trace
+        .lines()
+        .filter_map(|line| line.split_once("ls-remote< ").map(|(_, payload)| payload))
+        .filter_map(|payload| {
+            payload.strip_prefix("fetch=").or_else(|| {
+                payload
+                    .split_once("\\0")
+                    .map(|(_, capabilities)| capabilities)
+            })
+        })
+        .any(|capabilities| capabilities.split(' ').any(|c| c == "filter"));
+    Ok(supports)
+}
+
+pub fn clone(url: &str, path: &Path, branch: &str, mode: CacheMode) -> Result<(), Error> {
+    let mut args = vec!["clone", "--quiet", FILTER];
+    match mode {
+        CacheMode::Worktree => args.extend(["--branch", branch]),
+        CacheMode::Bare => args.extend(["--bare", "--single-branch", "--branch", branch]),
+        CacheMode::Mirror => args.push("--mirror"),
+    }
+    let path = path.to_string_lossy();
+    args.extend(["--", url, &path]);
+    git(None, &args)
+}
+
+/// Fetches `refspecs` asking only for missing commits and trees.
+pub fn fetch(path: &Path, refspecs: &[String], prune: bool) -> Result<(), Error> {
+    // `--update-head-ok` lets a branch glob cover the checked out branch of a working tree,
+    // it's reset to the remote right after.
+    let mut args = vec!["fetch", "--quiet", "--force", "--update-head-ok", FILTER];
+    if prune {
+        args.push("--prune");
+    }
+    args.push("origin");
+    args.extend(refspecs.iter().map(String::as_str));
+    git(Some(path), &args)
+}
+
+/// Same as the `libgit2` reset of a working tree cache, blobs of the new tree are fetched.
+pub fn reset_to_remote(path: &Path, branch: &str) -> Result<(), Error> {
+    let remote = format!("refs/remotes/origin/{branch}");
+    git(
+        Some(path),
+        &["checkout", "--quiet", "--force", "-B", branch, &remote],
+    )?;
+    git(Some(path), &["clean", "--quiet", "--force", "-d"])
+}
+
+/// Diff stats of the commit against its first parent, missing blobs are fetched in one batch.
+pub fn commit_stats(
+    path: &Path,
+    commit: &git2::Commit<'_>,
+    paths: &PathFilter,
+) -> Result<CommitStats, Error> {
+    let id = commit.id().to_string();
+    let mut args = vec![
+        "diff-tree",
+        "-r",
+        "-z",
+        "--numstat",
+        "--no-commit-id",
+        "--patience",
+        "-M",
+    ];
+    let parent = commit.parent_id(0).ok().map(|id: Oid| id.to_string());
+    match &parent {
+        Some(parent) => args.push(parent),
+        None => args.push("--root"),
+    }
+    args.push(&id);
+    output(Some(path), &args).map(|output| parse_numstat(&output, paths))
+}
+
+/// Reads a blob missing in the clone.
+pub fn read_blob(path: &Path, id: Oid) -> Result<String, Error> {
+    output(Some(path), &["cat-file", "blob", &id.to_string()])
+}
+
+/// Fetches the blobs `libgit2` needs to merge the parents of a merge commit again, which are
+/// the ones `--remerge-diff` reads. Its own diff would count the conflict markers as changes.
+pub fn fetch_merge_blobs(path: &Path, commit: &git2::Commit<'_>) -> Result<(), Error> {
+    let id = commit.id().to_string();
+    git(
+        Some(path),
+        &["show", "--remerge-diff", "--format=", "--stat", &id],
+    )
+}
+
+/// Fetches the blobs of the first-parent diff of the commit, `libgit2` reads its hunks then.
+pub fn fetch_commit_blobs(path: &Path, commit: &git2::Commit<'_>) -> Result<(), Error> {
+    let id = commit.id().to_string();
+    git(Some(path), &["show", "--format=", "--stat", &id])
+}
+
+/// Same as the `libgit2` blame of `lines` of the file in `commit`, missing blobs of its history
+/// are fetched.
+pub fn blame_lines(
+    path: &Path,
+    commit: Oid,
+    file: &Path,
+    lines: &[usize],
+) -> Result<HashMap<usize, LineOrigin>, Error> {
+    // One range per run of consecutive lines keeps the command line short.
+    let mut runs: Vec<(usize, usize)> = vec![];
+    for lineno in lines.iter().copied().sorted() {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == lineno => *end = lineno,
+            _ => runs.push((lineno, lineno)),
+        }
+    }
+    let ranges = runs
+        .iter()
+        .map(|(start, end)| format!("-L{start},{end}"))
+        .collect::<Vec<_>>();
+    let id = commit.to_string();
+    let file = file.to_string_lossy();
+    let mut args = vec!["blame", "--line-porcelain"];
+    args.extend(ranges.iter().map(String::as_str));
+    args.extend([id.as_str(), "--", &file]);
+    output(Some(path), &args).map(|output| parse_line_porcelain(&output))
+}
+
+/// Same as the `libgit2` blame of the whole file in `commit`, in line order.
+pub fn blame_file(path: &Path, commit: Oid, file: &Path) -> Result<Vec<LineOrigin>, Error> {
+    let id = commit.to_string();
+    let file = file.to_string_lossy();
+    let args = ["blame", "--line-porcelain", id.as_str(), "--", &file];
+    output(Some(path), &args).map(|output| {
+        parse_line_porcelain(&output)
+            .into_iter()
+            .sorted_by_key(|(lineno, _)| *lineno)
+            .map(|(_, origin)| origin)
+            .collect()
+    })
+}
+
+/// Parses `--line-porcelain` output, every line has a `<sha> <orig> <final>` header, the
+/// author headers and the content after a tab.
+fn parse_line_porcelain(output: &str) -> HashMap<usize, LineOrigin> {
+    let mut origins = HashMap::new();
+    let (mut lineno, mut name, mut email, mut time) = (None, "", "", 0);
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            if let Some(lineno) = lineno.take() {
+                let origin = LineOrigin {
+                    name: name.to_string(),
+                    email: email.trim_matches(['<', '>']).to_string(),
+                    time,
+                };
+                origins.insert(lineno, origin);
+            }
+        } else if let Some(value) = line.strip_prefix("author-mail ") {
+            email = value;
+        } else if let Some(value) = line.strip_prefix("author-time ") {
+            time = value.parse().unwrap_or(0);
+        } else if let Some(value) = line.strip_prefix("author ") {
+            name = value;
+        } else if lineno.is_none() {
+            lineno = line.split(' ').nth(2).and_then(|n| n.parse().ok());
+        }
+    }
+    origins
+}
+
+/// Parses `--numstat -z` output, a rename is `added\tdeleted\t\0old\0new\0` and is matched by
+/// the new path like `libgit2` deltas.
+fn parse_numstat(output: &str, paths: &PathFilter) -> CommitStats {
+    let mut stats = CommitStats::default();
+    let mut fields = output.split('\0');
+    while let Some(field) = fields.next().filter(|field| !field.is_empty()) {
+        let mut columns = field.splitn(3, '\t');
+        // Binary files are reported as `-`, `libgit2` doesn't count their lines either.
+        let insertions = columns.next().and_then(|s| s.parse::<usize>().ok());
+        let deletions = columns.next().and_then(|s| s.parse::<usize>().ok());
+        let path = match columns.next() {
+            Some("") => fields.nth(1),
+            path => path,
+        };
+        let Some(path) = path.map(Path::new) else {
+            continue;
+        };
+        if paths.is_counted(path) {
+            stats.add_file(path, insertions.unwrap_or(0), deletions.unwrap_or(0));
+        }
+    }
+    stats
+}
+
+fn git(dir: Option<&Path>, args: &[&str]) -> Result<(), Error> {
+    output(dir, args).map(|_| ())
+}
+
+fn output(dir: Option<&Path>, args: &[&str]) -> Result<String, Error> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+    command.args(args);
+    let output = run(&mut command, args.first().unwrap_or(&""))?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn run(command: &mut Command, name: &str) -> Result<Output, Error> {
+    let output = command.output().map_err(|e| {
+        let message = format!("Can't run `git`: {e}");
+        Error::new(ErrorCode::GenericError, ErrorClass::Os, message)
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::from_str(&format!(
+            "`git {name}` failed: {}",
+            stderr.trim()
+        )));
+    }
+    Ok(output)
+}
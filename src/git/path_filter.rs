@@ -0,0 +1,95 @@
+use git2::Error;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+
+const LINGUIST_ATTRIBUTES: [&str; 2] = ["linguist-generated", "linguist-vendored"];
+
+/// Decides which files count towards line statistics, patterns follow `.gitignore` syntax.
+#[derive(Debug)]
+pub struct PathFilter {
+    include: Gitignore,
+    exclude: Gitignore,
+    fingerprint: String,
+}
+
+// Create
+impl PathFilter {
+    /// Later patterns win, so `exclude` overrides the `gitattributes` and can re-include paths
+    /// with `!`. An empty `include` counts every path.
+    pub fn new(
+        include: &[String],
+        exclude: &[String],
+        gitattributes: Option<&str>,
+    ) -> Result<Self, Error> {
+        let generated = gitattributes.map(linguist_patterns).unwrap_or_default();
+        let exclude = [generated, exclude.to_vec()].concat();
+        Ok(Self {
+            fingerprint: format!("include={include:?};exclude={exclude:?}"),
+            include: build(include)?,
+            exclude: build(&exclude)?,
+        })
+    }
+}
+
+// Matching
+impl PathFilter {
+    pub fn is_counted(&self, path: &Path) -> bool {
+        let included = self.include.is_empty()
+            || self
+                .include
+                .matched_path_or_any_parents(path, false)
+                .is_ignore();
+        included
+            && !self
+                .exclude
+                .matched_path_or_any_parents(path, false)
+                .is_ignore()
+    }
+
+    /// Changes with the patterns, stats counted with other patterns must not be reused.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+}
+
+fn build(patterns: &[String]) -> Result<Gitignore, Error> {
+    let mut builder = GitignoreBuilder::new("");
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .map_err(|e| Error::from_str(&format!("Invalid path pattern `{pattern}`: {e}")))?;
+    }
+    builder.build().map_err(|e| Error::from_str(&e.to_string()))
+}
+
+/// Turns `linguist-generated` and `linguist-vendored` of a `.gitattributes` into exclude
+/// patterns, unset or `false` values into re-includes.
+fn linguist_patterns(gitattributes: &str) -> Vec<String> {
+    let mut patterns = vec![];
+    for line in gitattributes.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut columns = line.split_whitespace();
+        let Some(pattern) = columns.next() else {
+            continue;
+        };
+        for attribute in columns {
+            let (name, value) = match attribute.split_once('=') {
+                Some((name, value)) => (name, value != "false"),
+                None => match attribute.strip_prefix(['-', '!']) {
+                    Some(name) => (name, false),
+                    None => (attribute, true),
+                },
+            };
+            if LINGUIST_ATTRIBUTES.contains(&name) {
+                patterns.push(if value {
+                    pattern.to_string()
+                } else {
+                    format!("!{pattern}")
+                });
+            }
+        }
+    }
+    patterns
+}
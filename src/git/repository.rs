@@ -1,181 +1,332 @@
+use crate::git::partial;
 use crate::model;
+use clap::ValueEnum;
 use git2::build::CheckoutBuilder;
 use git2::{
-    build::RepoBuilder, Config, Error, FetchOptions, RemoteCallbacks, Repository, ResetType,
+    build::RepoBuilder, Config, Error, FetchOptions, FetchPrune, RemoteCallbacks, Repository,
+    ResetType,
 };
 use git2_credentials::CredentialHandler;
 use model::Repository as Repo;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub type PercentProgress<'a> = Box<dyn FnMut(usize, usize) + 'a>;
 
+/// How repositories are kept in the cache directory.
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CacheMode {
+    /// `{name}` with a checked out working tree.
+    Worktree,
+    /// `{name}.git` without a working tree, only the configured branches are fetched.
+    Bare,
+    /// `{name}.git` without a working tree, all remote refs are mirrored.
+    Mirror,
+}
+
 pub trait GitRepository {
-    fn repo_exists(&self, dir_path: &str) -> bool;
-    fn repo_clone<'a>(&self, dir_path: &str, cb: PercentProgress<'a>) -> Result<Repository, Error>;
-    fn repo_pull<'a>(&self, dir_path: &str, cb: PercentProgress<'a>) -> Result<Repository, Error>;
+    fn repo_path(&self, dir_path: &str, mode: CacheMode) -> PathBuf;
+    fn repo_exists(&self, dir_path: &str, mode: CacheMode) -> bool;
+    fn stats_cache_path(&self, dir_path: &str) -> PathBuf;
+    /// Clones the repository, with the reason when a partial clone was asked for but a full
+    /// one was made.
+    fn repo_clone<'a>(
+        &self,
+        dir_path: &str,
+        mode: CacheMode,
+        cb: PercentProgress<'a>,
+    ) -> Result<(Repository, Option<String>), Error>;
+    fn repo_pull<'a>(
+        &self,
+        dir_path: &str,
+        mode: CacheMode,
+        cb: PercentProgress<'a>,
+    ) -> Result<Repository, Error>;
 }
 
 impl GitRepository for Repo {
-    fn repo_exists(&self, dir_path: &str) -> bool {
-        let path = Path::new(dir_path).join(&self.name);
-        path.exists()
+    fn repo_path(&self, dir_path: &str, mode: CacheMode) -> PathBuf {
+        match mode {
+            CacheMode::Worktree => Path::new(dir_path).join(&self.name),
+            CacheMode::Bare | CacheMode::Mirror => {
+                Path::new(dir_path).join(format!("{}.git", self.name))
+            }
+        }
+    }
+
+    fn repo_exists(&self, dir_path: &str, mode: CacheMode) -> bool {
+        self.repo_path(dir_path, mode).exists()
+    }
+
+    fn stats_cache_path(&self, dir_path: &str) -> PathBuf {
+        Path::new(dir_path).join(format!("{}.stats.json", self.name))
     }
 
     fn repo_clone<'a>(
         &self,
         dir_path: &str,
+        mode: CacheMode,
         mut cb: PercentProgress<'a>,
-    ) -> Result<Repository, Error> {
-        let git_config = Config::open_default()?;
-        let mut credential_handler = CredentialHandler::new(git_config);
-
-        let mut callbacks = RemoteCallbacks::new();
-        callbacks.credentials(move |url, username, allowed| {
-            credential_handler.try_next_credential(url, username, allowed)
-        });
-        callbacks.transfer_progress(|stats| {
-            let current = 100.0 / stats.total_objects() as f64 * stats.received_objects() as f64;
-            cb(current as usize, 100);
-            true
-        });
-
-        let mut options = FetchOptions::new();
-        options.remote_callbacks(callbacks);
-
-        let path = Path::new(dir_path).join(&self.name);
-        RepoBuilder::new()
-            .fetch_options(options)
-            .branch(&self.branch)
-            .clone(&self.ssh, &path)
+    ) -> Result<(Repository, Option<String>), Error> {
+        let path = self.repo_path(dir_path, mode);
+        let fallback = match self.partial_clone {
+            true => partial::unsupported_reason(&self.ssh)?,
+            false => None,
+        };
+        let is_partial = self.partial_clone && fallback.is_none();
+        if is_partial {
+            if let Err(err) = partial::clone(&self.ssh, &path, &self.branch, mode) {
+                if path.exists() {
+                    fs::remove_dir_all(&path).map_err(|e| Error::from_str(&e.to_string()))?;
+                }
+                return Err(err);
+            }
+        } else {
+            let mut options = fetch_options(&mut cb)?;
+            match mode {
+                CacheMode::Worktree => {
+                    RepoBuilder::new()
+                        .fetch_options(options)
+                        .branch(&self.branch)
+                        .clone(&self.ssh, &path)?;
+                }
+                // `RepoBuilder` would fetch every branch with the default refspec.
+                CacheMode::Bare => {
+                    let refspec = branch_refspec(&self.branch);
+                    let repo = Repository::init_bare(&path)?;
+                    repo.remote_with_fetch("origin", &self.ssh, &refspec)?
+                        .fetch(&[&refspec], Some(&mut options), None)?;
+                    repo.set_head(&format!("refs/heads/{}", self.branch))?;
+                }
+                // A mirror has no remote-tracking refs to create the branch from, it gets
+                // `refs/heads/<branch>` as is.
+                CacheMode::Mirror => {
+                    RepoBuilder::new()
+                        .fetch_options(options)
+                        .bare(true)
+                        .remote_create(|repo, name, url| {
+                            repo.remote_with_fetch(name, url, MIRROR_REFSPEC)
+                        })
+                        .clone(&self.ssh, &path)?;
+                }
+            }
+        }
+        // A clone only creates the configured branch, the additional ones are fetched as on a
+        // pull.
+        let repo = if !self.branches.is_empty() && mode != CacheMode::Mirror {
+            self.repo_pull(dir_path, mode, cb)?
+        } else {
+            Repository::open(&path)?
+        };
+        Ok((repo, fallback))
     }
 
     fn repo_pull<'a>(
         &self,
         dir_path: &str,
+        mode: CacheMode,
         mut cb: PercentProgress<'a>,
     ) -> Result<Repository, Error> {
-        let git_config = Config::open_default()?;
-        let mut credential_handler = CredentialHandler::new(git_config);
-
-        let mut callbacks = RemoteCallbacks::new();
-        callbacks.credentials(move |url, username, allowed| {
-            credential_handler.try_next_credential(url, username, allowed)
-        });
-        callbacks.transfer_progress(|stats| {
-            let current = 100.0 / stats.total_objects() as f64 * stats.received_objects() as f64;
-            cb(current as usize, 100);
-            true
-        });
-
-        let mut options = FetchOptions::new();
-        options.remote_callbacks(callbacks);
-
-        let path = Path::new(dir_path).join(&self.name);
-        let repo = Repository::open(path)?;
-        let mut remote = repo.find_remote("origin")?;
-        reset(&repo, &self.branch)?;
-        checkout(&repo, &self.branch)?;
-        remote.fetch(&[&self.branch], Some(&mut options), None)?;
-        let fetch_head = repo.find_reference("FETCH_HEAD")?;
-        let fetch_commit = repo.reference_to_annotated_commit(&fetch_head)?;
-        merge(&repo, &self.branch, fetch_commit)?;
-
-        let path = Path::new(dir_path).join(&self.name);
-        Repository::open(path)
+        let path = self.repo_path(dir_path, mode);
+        let repo = Repository::open(&path)?;
+        if partial::is_partial(&repo) {
+            let refspecs = fetch_refspecs(self, mode);
+            partial::fetch(&path, &refspecs, mode == CacheMode::Mirror)?;
+            if mode == CacheMode::Worktree {
+                partial::reset_to_remote(&path, &self.branch)?;
+            }
+            cb(100, 100);
+            return Ok(repo);
+        }
+
+        let mut options = fetch_options(&mut cb)?;
+        if mode == CacheMode::Mirror {
+            options.prune(FetchPrune::On);
+        }
+        let refspecs = fetch_refspecs(self, mode);
+        repo.find_remote("origin")?
+            .fetch(&refspecs, Some(&mut options), None)?;
+        if mode == CacheMode::Worktree {
+            reset_to_remote(&repo, &self.branch)?;
+        }
+        Ok(repo)
     }
 }
 
-fn reset(repo: &Repository, branch: &str) -> Result<(), Error> {
-    let rev = repo.revparse_single(branch)?;
-    repo.reset(&rev, ResetType::Hard, None)
+const MIRROR_REFSPEC: &str = "+refs/*:refs/*";
+
+/// Every mode ends up with the walked branches under `refs/heads`, a working tree gets its
+/// branch there by `reset_to_remote`.
+fn fetch_refspecs(repo: &Repo, mode: CacheMode) -> Vec<String> {
+    let branch = &repo.branch;
+    let head = match mode {
+        CacheMode::Worktree => format!("+refs/heads/{branch}:refs/remotes/origin/{branch}"),
+        CacheMode::Bare => branch_refspec(branch),
+        CacheMode::Mirror => return vec![MIRROR_REFSPEC.to_string()],
+    };
+    let others = repo.branches.iter().map(|branch| branch_refspec(branch));
+    [head].into_iter().chain(others).collect()
 }
 
-fn checkout(repo: &Repository, branch: &str) -> Result<(), Error> {
-    let (object, _) = repo.revparse_ext(branch)?;
-    repo.checkout_tree(&object, None)
+fn branch_refspec(branch: &str) -> String {
+    format!("+refs/heads/{branch}:refs/heads/{branch}")
 }
 
-fn merge<'a>(
-    repo: &'a Repository,
-    branch: &str,
-    fetch_commit: git2::AnnotatedCommit<'a>,
-) -> Result<(), Error> {
-    let analysis = repo.merge_analysis(&[&fetch_commit])?;
-
-    if analysis.0.is_fast_forward() {
-        let refname = format!("refs/heads/{}", branch);
-        match repo.find_reference(&refname) {
-            Ok(mut r) => {
-                fast_forward(repo, &mut r, &fetch_commit)?;
-            }
-            Err(_) => {
-                repo.reference(
-                    &refname,
-                    fetch_commit.id(),
-                    true,
-                    &format!("Setting {} to {}", branch, fetch_commit.id()),
-                )?;
-                repo.set_head(&refname)?;
-                repo.checkout_head(Some(
-                    CheckoutBuilder::default()
-                        .allow_conflicts(true)
-                        .conflict_style_merge(true)
-                        .force(),
-                ))?;
-            }
-        };
-    } else if analysis.0.is_normal() {
-        let head_commit = repo.reference_to_annotated_commit(&repo.head()?)?;
-        normal_merge(&repo, &head_commit, &fetch_commit)?;
-    }
-    Ok(())
+fn fetch_options<'a>(cb: &'a mut PercentProgress<'_>) -> Result<FetchOptions<'a>, Error> {
+    let git_config = Config::open_default()?;
+    let mut credential_handler = CredentialHandler::new(git_config);
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        credential_handler.try_next_credential(url, username, allowed)
+    });
+    callbacks.transfer_progress(|stats| {
+        let current = 100.0 / stats.total_objects() as f64 * stats.received_objects() as f64;
+        cb(current as usize, 100);
+        true
+    });
+
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    Ok(options)
 }
 
-fn fast_forward(
-    repo: &Repository,
-    lb: &mut git2::Reference,
-    rc: &git2::AnnotatedCommit,
-) -> Result<(), Error> {
-    let name = match lb.name() {
-        Some(s) => s.to_string(),
-        None => String::from_utf8_lossy(lb.name_bytes()).to_string(),
-    };
-    let msg = format!("Fast-Forward: Setting {} to id: {}", name, rc.id());
-    lb.set_target(rc.id(), &msg)?;
-    repo.set_head(&name)?;
-    repo.checkout_head(Some(CheckoutBuilder::default().force()))?;
-    Ok(())
+/// Points the local branch and `HEAD` at `origin/<branch>` and discards any local state,
+/// so the cache always mirrors the remote even after a force push.
+fn reset_to_remote(repo: &Repository, branch: &str) -> Result<(), Error> {
+    let remote_ref = repo.find_reference(&format!("refs/remotes/origin/{branch}"))?;
+    let commit = remote_ref.peel_to_commit()?;
+    let refname = format!("refs/heads/{branch}");
+    repo.reference(
+        &refname,
+        commit.id(),
+        true,
+        &format!("Reset {branch} to origin/{branch} ({})", commit.id()),
+    )?;
+    repo.set_head(&refname)?;
+    repo.reset(commit.as_object(), ResetType::Hard, None)?;
+    repo.checkout_head(Some(
+        CheckoutBuilder::default().force().remove_untracked(true),
+    ))
 }
 
-fn normal_merge(
-    repo: &Repository,
-    local: &git2::AnnotatedCommit,
-    remote: &git2::AnnotatedCommit,
-) -> Result<(), Error> {
-    let local_tree = repo.find_commit(local.id())?.tree()?;
-    let remote_tree = repo.find_commit(remote.id())?.tree()?;
-    let ancestor = repo
-        .find_commit(repo.merge_base(local.id(), remote.id())?)?
-        .tree()?;
-    let mut idx = repo.merge_trees(&ancestor, &local_tree, &remote_tree, None)?;
-
-    if idx.has_conflicts() {
-        repo.checkout_index(Some(&mut idx), None)?;
-        return Ok(());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{CommitStatsCache, GitCommitRepository};
+    use crate::model::MergePolicy;
+    use crate::test_support::{commit, git_repository, query, repository, BASE_TIME};
+    use tempfile::TempDir;
+
+    fn source() -> TempDir {
+        let (dir, repo) = git_repository();
+        repo.config()
+            .unwrap()
+            .set_bool("uploadpack.allowFilter", true)
+            .unwrap();
+        let readme = "# App\n\nAbout the app.\n";
+        let first = commit(
+            &repo,
+            "main",
+            &[],
+            "alice",
+            BASE_TIME,
+            &[
+                ("src/lib.rs", "fn a() {}\nfn b() {}\nfn c() {}\n"),
+                ("README.md", readme),
+            ],
+        );
+        let second = commit(
+            &repo,
+            "main",
+            &[first],
+            "alice",
+            BASE_TIME + 60,
+            &[
+                (
+                    "src/lib.rs",
+                    "fn a() {}\nfn b() { b() }\nfn c() {}\nfn d() {}\n",
+                ),
+                ("docs/README.md", readme),
+            ],
+        );
+        commit(
+            &repo,
+            "main",
+            &[second],
+            "alice",
+            BASE_TIME + 120,
+            &[("docs/README.md", readme)],
+        );
+        dir
+    }
+
+    fn clone(
+        source: &TempDir,
+        cache: &TempDir,
+        name: &str,
+        partial_clone: bool,
+    ) -> (Repository, Option<String>) {
+        let repo = Repo {
+            ssh: format!("file://{}", source.path().display()),
+            partial_clone,
+            ..repository(name)
+        };
+        let dir_path = cache.path().to_string_lossy();
+        repo.repo_clone(&dir_path, CacheMode::Bare, Box::new(|_, _| {}))
+            .unwrap()
+    }
+
+    #[test]
+    fn partial_clone_counts_same_lines_as_full_clone() {
+        let source = source();
+        let cache = TempDir::new().unwrap();
+        let (full, _) = clone(&source, &cache, "full", false);
+        let (partial, fallback) = clone(&source, &cache, "partial", true);
+        assert_eq!(fallback, None);
+        assert!(!partial::is_partial(&full));
+        assert!(partial::is_partial(&partial));
+
+        let query = query(MergePolicy::Skip);
+        let stats = |repo: &Repository| {
+            let mut cache = CommitStatsCache::open(cache.path().join("stats.json"), "");
+            let (commits, _) = repo.get_commits(&query, &mut cache).unwrap();
+            commits
+                .into_iter()
+                .map(|c| {
+                    (
+                        c.files_changed,
+                        c.insertions,
+                        c.deletions,
+                        c.extensions,
+                        c.files,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let full_stats = stats(&full);
+        assert_eq!(full_stats.len(), 3);
+        // The rename of the readme counts as one changed file without lines.
+        assert_eq!(full_stats[1].0, 2);
+        assert_eq!((full_stats[1].1, full_stats[1].2), (2, 1));
+        assert_eq!(stats(&partial), full_stats);
+    }
+
+    #[test]
+    fn falls_back_to_full_clone_without_filter_support() {
+        let source = source();
+        let config = git2::Repository::open(source.path()).unwrap().config();
+        config
+            .unwrap()
+            .set_bool("uploadpack.allowFilter", false)
+            .unwrap();
+        let cache = TempDir::new().unwrap();
+        let (repo, fallback) = clone(&source, &cache, "app", true);
+        assert_eq!(
+            fallback.as_deref(),
+            Some("the server doesn't support filters")
+        );
+        assert!(!partial::is_partial(&repo));
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/main"));
     }
-    let result_tree = repo.find_tree(idx.write_tree_to(repo)?)?;
-    let msg = format!("Merge: {} into {}", remote.id(), local.id());
-    let sig = repo.signature()?;
-    let local_commit = repo.find_commit(local.id())?;
-    let remote_commit = repo.find_commit(remote.id())?;
-    let _merge_commit = repo.commit(
-        Some("HEAD"),
-        &sig,
-        &sig,
-        &msg,
-        &result_tree,
-        &[&local_commit, &remote_commit],
-    )?;
-    repo.checkout_head(None)?;
-    Ok(())
 }
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Lines per file type, see `file_type`.
+    #[serde(default)]
+    pub extensions: BTreeMap<String, LineStats>,
+    /// Counted files, the new path of a renamed one.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Absent unless the churn window was set, and for merge commits.
+    #[serde(default)]
+    pub churn: Option<Churn>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LineStats {
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Lines of a commit by how recently the lines it changes were written.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Churn {
+    /// Inserted lines of the hunks that change no recently written line.
+    pub new_work: usize,
+    /// Recently written lines the commit changes or deletes, by the email of their author.
+    pub rework: BTreeMap<String, usize>,
+}
+
+impl CommitStats {
+    pub fn add_file(&mut self, path: &Path, insertions: usize, deletions: usize) {
+        self.files_changed += 1;
+        self.insertions += insertions;
+        self.deletions += deletions;
+        self.files.push(path.to_string_lossy().into_owned());
+        let lines = self.extensions.entry(file_type(path)).or_default();
+        lines.insertions += insertions;
+        lines.deletions += deletions;
+    }
+}
+
+/// Lowercase extension of the file, or its lowercase name when it has none like `dockerfile`.
+pub fn file_type(path: &Path) -> String {
+    path.extension()
+        .or(path.file_name())
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    fingerprint: String,
+    commits: HashMap<String, CommitStats>,
+}
+
+/// On-disk stats of already diffed commits, keyed by commit id.
+///
+/// The whole cache is dropped when it was written with other diff settings (`fingerprint`).
+pub struct CommitStatsCache {
+    path: PathBuf,
+    file: CacheFile,
+    changed: bool,
+}
+
+impl CommitStatsCache {
+    pub fn open(path: impl AsRef<Path>, fingerprint: &str) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let file = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<CacheFile>(&json).ok())
+            .filter(|file| file.fingerprint == fingerprint)
+            .unwrap_or_else(|| CacheFile {
+                fingerprint: fingerprint.to_string(),
+                commits: HashMap::new(),
+            });
+        Self {
+            path,
+            file,
+            changed: false,
+        }
+    }
+
+    pub fn get(&self, oid: &git2::Oid) -> Option<CommitStats> {
+        self.file.commits.get(&oid.to_string()).cloned()
+    }
+
+    pub fn insert(&mut self, oid: &git2::Oid, stats: CommitStats) {
+        self.file.commits.insert(oid.to_string(), stats);
+        self.changed = true;
+    }
+
+    pub fn len(&self) -> usize {
+        self.file.commits.len()
+    }
+
+    pub fn save(&mut self) -> io::Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(&self.file)?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.changed = false;
+        Ok(())
+    }
+}
@@ -1,5 +1,3 @@
 pub mod pull_request;
 
-pub use pull_request::PullRequest;
-pub use pull_request::GiteaPullRequest;
-pub use pull_request::GiteaPullReview;
+pub use pull_request::GiteaForge;
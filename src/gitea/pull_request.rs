@@ -1,53 +1,44 @@
-use crate::model::{Repository, Sprint};
+use crate::forge::{Forge, ForgeUser, PageProgress, PullRequest, PullReview, Result, ReviewState};
+use crate::model::Repository;
 use chrono::{DateTime, FixedOffset};
-use gitea_sdk::error::Result;
 use gitea_sdk::model::issues::State;
-use gitea_sdk::Client;
+use gitea_sdk::model::reviews::ReviewStateType;
+use gitea_sdk::{Auth, Client};
 
-pub type GiteaPullRequest = gitea_sdk::model::pulls::PullRequest;
-pub type GiteaPullReview = gitea_sdk::model::reviews::PullReview;
+type GiteaPullRequest = gitea_sdk::model::pulls::PullRequest;
+type GiteaPullReview = gitea_sdk::model::reviews::PullReview;
+type GiteaUser = gitea_sdk::model::user::User;
 
-#[derive(Debug, Clone)]
-pub struct PullRequest {
-    pub request: GiteaPullRequest,
-    pub reviews: Vec<GiteaPullReview>,
+pub struct GiteaForge {
+    client: Client,
 }
 
-impl PullRequest {
-    fn new(request: GiteaPullRequest, reviews: Vec<GiteaPullReview>) -> Self {
-        Self { request, reviews }
+impl GiteaForge {
+    pub fn new(url: &str, token: &str) -> Self {
+        Self {
+            client: Client::new(url, Auth::Token(token)),
+        }
     }
 }
 
-pub type PercentProgress<'a> = Box<dyn FnMut(i64) + Send +'a>;
-
-pub trait GiteaPullRequester {
-    async fn fetch_pull_request<'a>(
-        &self,
-        client: &Client,
-        since: &DateTime<FixedOffset>,
-        cb: PercentProgress<'a>,
-    ) -> Result<Vec<PullRequest>>;
-}
-
-impl GiteaPullRequester for Repository {
-    async fn fetch_pull_request<'a>(
+impl Forge for GiteaForge {
+    async fn fetch_pull_requests<'a>(
         &self,
-        client: &Client,
+        repo: &Repository,
         since: &DateTime<FixedOffset>,
-        mut cb: PercentProgress<'a>,
+        mut cb: PageProgress<'a>,
     ) -> Result<Vec<PullRequest>> {
-        fn datetime_more_since(datetime: &Option<String>, since: &DateTime<FixedOffset>) -> bool {
-            let Some(datetime) = datetime else {
-                return false;
-            };
-            let datetime = DateTime::parse_from_rfc3339(&datetime).unwrap();
-            datetime >= *since
+        fn datetime_more_since(
+            datetime: &Option<DateTime<FixedOffset>>,
+            since: &DateTime<FixedOffset>,
+        ) -> bool {
+            datetime.is_some_and(|datetime| datetime >= *since)
         }
 
+        let client = &self.client;
         let mut page = 1;
         let mut pull_requests: Vec<PullRequest> = vec![];
-        let pulls = client.pulls(&self.owner, &self.name);
+        let pulls = client.pulls(&repo.owner, &repo.name);
 
         loop {
             cb(page);
@@ -56,36 +47,75 @@ impl GiteaPullRequester for Repository {
                 .limit(20)
                 .page(page)
                 .state(State::All)
-                .send(&client)
+                .send(client)
                 .await?;
-            let gitea_pull_requests = gitea_pull_requests
-                .iter()
-                .filter(|pull_request| {
-                    datetime_more_since(&Some(pull_request.created_at.to_string()), since)
-                        || datetime_more_since(&pull_request.merged_at, since)
-                        || datetime_more_since(&pull_request.closed_at, since)
-                })
-                .collect::<Vec<_>>();
-            if gitea_pull_requests.is_empty() {
+            let mut page_pull_requests = vec![];
+            for gitea_pull_request in &gitea_pull_requests {
+                let pull_request = gitea_to_pull_request(gitea_pull_request)?;
+                if datetime_more_since(&Some(pull_request.created_at), since)
+                    || datetime_more_since(&pull_request.merged_at, since)
+                    || datetime_more_since(&pull_request.closed_at, since)
+                {
+                    page_pull_requests.push(pull_request);
+                }
+            }
+            if page_pull_requests.is_empty() {
                 break;
             }
 
-            let gitea_reviews = futures::future::join_all(
-                gitea_pull_requests
+            let gitea_reviews = futures::future::try_join_all(
+                page_pull_requests
                     .iter()
-                    .map(|pr| async { pulls.reviews().get(pr.number).send(&client).await.unwrap() })
+                    .map(|pr| async { pulls.reviews().get(pr.number).send(client).await })
                     .collect::<Vec<_>>(),
             )
-            .await;
+            .await?;
 
-            for index in 0..gitea_pull_requests.len() {
-                pull_requests.push(PullRequest::new(
-                    gitea_pull_requests[index].clone(),
-                    gitea_reviews[index].clone(),
-                ))
+            for (mut pull_request, reviews) in page_pull_requests.into_iter().zip(gitea_reviews) {
+                pull_request.reviews = reviews.iter().map(gitea_to_pull_review).collect();
+                pull_requests.push(pull_request);
             }
             page += 1;
         }
         Ok(pull_requests)
     }
 }
+
+fn gitea_to_pull_request(pull_request: &GiteaPullRequest) -> Result<PullRequest> {
+    fn parse(datetime: &Option<String>) -> Result<Option<DateTime<FixedOffset>>> {
+        match datetime {
+            Some(datetime) => Ok(Some(DateTime::parse_from_rfc3339(datetime)?)),
+            None => Ok(None),
+        }
+    }
+
+    Ok(PullRequest::new(
+        pull_request.number,
+        gitea_to_forge_user(&pull_request.user),
+        DateTime::parse_from_rfc3339(&pull_request.created_at)?,
+        parse(&pull_request.merged_at)?,
+        parse(&pull_request.closed_at)?,
+        vec![],
+    ))
+}
+
+fn gitea_to_pull_review(review: &GiteaPullReview) -> PullReview {
+    let state = match review.state {
+        _ if review.dismissed => ReviewState::Dismissed,
+        ReviewStateType::Approved => ReviewState::Approved,
+        ReviewStateType::Pending => ReviewState::Pending,
+        ReviewStateType::Comment => ReviewState::Comment,
+        ReviewStateType::RequestChanges => ReviewState::RequestChanges,
+        ReviewStateType::RequestReview => ReviewState::RequestReview,
+        ReviewStateType::Unknown => ReviewState::Unknown,
+    };
+    PullReview::new(
+        review.user.as_ref().map(gitea_to_forge_user),
+        state,
+        review.comments_count as usize,
+    )
+}
+
+fn gitea_to_forge_user(user: &GiteaUser) -> ForgeUser {
+    ForgeUser::new(&user.login, Some(&user.email))
+}
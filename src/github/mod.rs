@@ -0,0 +1,3 @@
+pub mod pull_request;
+
+pub use pull_request::GitHubForge;
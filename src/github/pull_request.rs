@@ -0,0 +1,321 @@
+use crate::forge::{Forge, ForgeUser, PageProgress, PullRequest, PullReview, Result, ReviewState};
+use crate::model::Repository;
+use chrono::{DateTime, FixedOffset};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const PAGE_LIMIT: usize = 20;
+const COMMENTS_PAGE_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct GitHubPullRequest {
+    number: i64,
+    user: Option<GitHubUser>,
+    created_at: DateTime<FixedOffset>,
+    updated_at: DateTime<FixedOffset>,
+    merged_at: Option<DateTime<FixedOffset>>,
+    closed_at: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubPullReview {
+    id: i64,
+    user: Option<GitHubUser>,
+    state: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubReviewComment {
+    pull_request_review_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubUser {
+    login: String,
+    email: Option<String>,
+}
+
+pub struct GitHubForge {
+    client: Client,
+    url: String,
+}
+
+impl GitHubForge {
+    pub fn new(url: &str, token: Option<&str>) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+        headers.insert(USER_AGENT, HeaderValue::from_static("technical-metrics"));
+        headers.insert(
+            "X-GitHub-Api-Version",
+            HeaderValue::from_static("2022-11-28"),
+        );
+        if let Some(token) = token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {token}"))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(Self {
+            client: Client::builder().default_headers(headers).build()?,
+            url: url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str, page: usize, limit: usize) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.url, path))
+            .query(&[("page", page), ("per_page", limit)])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    async fn fetch_reviews(&self, repo: &Repository, number: i64) -> Result<Vec<PullReview>> {
+        let path = format!("/repos/{}/{}/pulls/{}", repo.owner, repo.name, number);
+
+        let mut reviews: Vec<GitHubPullReview> = vec![];
+        for page in 1.. {
+            let page_reviews: Vec<GitHubPullReview> = self
+                .get(&format!("{path}/reviews"), page, COMMENTS_PAGE_LIMIT)
+                .await?;
+            let is_last = page_reviews.len() < COMMENTS_PAGE_LIMIT;
+            reviews.extend(page_reviews);
+            if is_last {
+                break;
+            }
+        }
+
+        let mut comments_count: HashMap<i64, usize> = HashMap::new();
+        for page in 1.. {
+            let page_comments: Vec<GitHubReviewComment> = self
+                .get(&format!("{path}/comments"), page, COMMENTS_PAGE_LIMIT)
+                .await?;
+            let is_last = page_comments.len() < COMMENTS_PAGE_LIMIT;
+            for review_id in page_comments
+                .iter()
+                .filter_map(|c| c.pull_request_review_id)
+            {
+                *comments_count.entry(review_id).or_default() += 1;
+            }
+            if is_last {
+                break;
+            }
+        }
+
+        let reviews = reviews
+            .into_iter()
+            .map(|review| {
+                PullReview::new(
+                    review.user.map(github_to_forge_user),
+                    github_to_review_state(&review.state),
+                    comments_count.get(&review.id).copied().unwrap_or(0),
+                )
+            })
+            .collect();
+        Ok(reviews)
+    }
+}
+
+impl Forge for GitHubForge {
+    async fn fetch_pull_requests<'a>(
+        &self,
+        repo: &Repository,
+        since: &DateTime<FixedOffset>,
+        mut cb: PageProgress<'a>,
+    ) -> Result<Vec<PullRequest>> {
+        fn datetime_more_since(
+            datetime: &Option<DateTime<FixedOffset>>,
+            since: &DateTime<FixedOffset>,
+        ) -> bool {
+            datetime.is_some_and(|datetime| datetime >= *since)
+        }
+
+        let path = format!(
+            "/repos/{}/{}/pulls?state=all&sort=updated&direction=desc",
+            repo.owner, repo.name
+        );
+        let mut page = 1;
+        let mut pull_requests: Vec<PullRequest> = vec![];
+
+        loop {
+            cb(page as i64);
+            let github_pull_requests: Vec<GitHubPullRequest> =
+                self.get(&path, page, PAGE_LIMIT).await?;
+            // Sorted by the last update, so nothing created, merged or closed since `since` is left.
+            if github_pull_requests.iter().all(|pr| pr.updated_at < *since) {
+                break;
+            }
+            let github_pull_requests = github_pull_requests
+                .into_iter()
+                .filter(|pr| {
+                    datetime_more_since(&Some(pr.created_at), since)
+                        || datetime_more_since(&pr.merged_at, since)
+                        || datetime_more_since(&pr.closed_at, since)
+                })
+                .collect::<Vec<_>>();
+
+            let github_reviews = futures::future::try_join_all(
+                github_pull_requests
+                    .iter()
+                    .map(|pr| self.fetch_reviews(repo, pr.number))
+                    .collect::<Vec<_>>(),
+            )
+            .await?;
+
+            for (pull_request, reviews) in github_pull_requests.into_iter().zip(github_reviews) {
+                pull_requests.push(PullRequest::new(
+                    pull_request.number,
+                    pull_request
+                        .user
+                        .map(github_to_forge_user)
+                        .unwrap_or_else(|| ForgeUser::new("ghost", None::<String>)),
+                    pull_request.created_at,
+                    pull_request.merged_at,
+                    pull_request.closed_at,
+                    reviews,
+                ));
+            }
+            page += 1;
+        }
+        Ok(pull_requests)
+    }
+}
+
+fn github_to_review_state(state: &str) -> ReviewState {
+    match state {
+        "APPROVED" => ReviewState::Approved,
+        "CHANGES_REQUESTED" => ReviewState::RequestChanges,
+        "COMMENTED" => ReviewState::Comment,
+        "PENDING" => ReviewState::Pending,
+        "DISMISSED" => ReviewState::Dismissed,
+        _ => ReviewState::Unknown,
+    }
+}
+
+fn github_to_forge_user(user: GitHubUser) -> ForgeUser {
+    ForgeUser::new(user.login, user.email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ForgeKind;
+    use crate::test_support::repository;
+    use mockito::{Matcher, Server};
+
+    fn pull_request(number: i64, author: &str, created_at: &str, updated_at: &str) -> String {
+        format!(
+            r#"{{"number":{number},"user":{{"login":"{author}"}},"created_at":"{created_at}","updated_at":"{updated_at}","merged_at":null,"closed_at":null}}"#
+        )
+    }
+
+    fn review(id: i64, author: &str, state: &str) -> String {
+        format!(r#"{{"id":{id},"user":{{"login":"{author}"}},"state":"{state}"}}"#)
+    }
+
+    fn comment(review_id: Option<i64>) -> String {
+        let review_id = review_id.map_or("null".to_string(), |id| id.to_string());
+        format!(r#"{{"pull_request_review_id":{review_id}}}"#)
+    }
+
+    #[tokio::test]
+    async fn fetches_pages_until_stale_and_counts_review_comments() {
+        let mut server = Server::new_async().await;
+        let pulls = "/repos/group/app/pulls";
+        let page = |page: &str| Matcher::UrlEncoded("page".into(), page.into());
+        let first_page = server
+            .mock("GET", pulls)
+            .match_query(page("1"))
+            .with_body(format!(
+                "[{},{}]",
+                pull_request(3, "alice", "2025-01-05T10:00:00Z", "2025-01-06T10:00:00Z"),
+                // Updated after `since` but opened before it.
+                pull_request(2, "bob", "2024-12-20T10:00:00Z", "2025-01-02T10:00:00Z")
+            ))
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", pulls)
+            .match_query(page("2"))
+            .with_body(format!(
+                "[{}]",
+                pull_request(1, "carol", "2024-12-01T10:00:00Z", "2024-12-10T10:00:00Z")
+            ))
+            .create_async()
+            .await;
+        let third_page = server
+            .mock("GET", pulls)
+            .match_query(page("3"))
+            .with_body("[]")
+            .expect(0)
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("{pulls}/3/reviews").as_str())
+            .match_query(Matcher::Any)
+            .with_body(format!(
+                "[{},{},{}]",
+                review(10, "bob", "APPROVED"),
+                review(11, "carol", "DISMISSED"),
+                review(12, "bob", "COMMENTED")
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("{pulls}/3/comments").as_str())
+            .match_query(Matcher::Any)
+            .with_body(format!(
+                "[{},{},{},{}]",
+                comment(Some(11)),
+                comment(Some(12)),
+                comment(Some(11)),
+                comment(None)
+            ))
+            .create_async()
+            .await;
+
+        let repo = Repository {
+            forge: ForgeKind::GitHub,
+            ..repository("app")
+        };
+        let forge = GitHubForge::new(&server.url(), None).unwrap();
+        let since = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap();
+        let mut pages = vec![];
+        let pull_requests = forge
+            .fetch_pull_requests(&repo, &since, Box::new(|page| pages.push(page)))
+            .await
+            .unwrap();
+
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+        third_page.assert_async().await;
+        assert_eq!(pages, vec![1, 2]);
+        assert_eq!(
+            pull_requests.iter().map(|pr| pr.number).collect::<Vec<_>>(),
+            vec![3]
+        );
+        let summary = pull_requests[0]
+            .reviews
+            .iter()
+            .map(|review| {
+                let author = review.author.as_ref().unwrap();
+                (author.login.as_str(), review.state, review.comments_count)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("bob", ReviewState::Approved, 0),
+                ("carol", ReviewState::Dismissed, 2),
+                ("bob", ReviewState::Comment, 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use crate::forge::{Forge, ForgeUser, PageProgress, PullRequest, PullReview, Result, ReviewState};
+use crate::model::Repository;
+use chrono::{DateTime, FixedOffset};
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::{Client, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const PAGE_LIMIT: usize = 20;
+const NOTES_PAGE_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct GitLabMergeRequest {
+    iid: i64,
+    author: GitLabUser,
+    created_at: DateTime<FixedOffset>,
+    merged_at: Option<DateTime<FixedOffset>>,
+    closed_at: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabApprovals {
+    #[serde(default)]
+    approved_by: Vec<GitLabApprover>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabApprover {
+    user: GitLabUser,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabDiscussion {
+    notes: Vec<GitLabNote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabNote {
+    author: GitLabUser,
+    system: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabUser {
+    username: String,
+    public_email: Option<String>,
+}
+
+pub struct GitLabForge {
+    client: Client,
+    url: Url,
+}
+
+impl GitLabForge {
+    pub fn new(url: &str, token: Option<&str>) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("technical-metrics"));
+        if let Some(token) = token {
+            let mut value = HeaderValue::from_str(token)?;
+            value.set_sensitive(true);
+            headers.insert("PRIVATE-TOKEN", value);
+        }
+        Ok(Self {
+            client: Client::builder().default_headers(headers).build()?,
+            url: Url::parse(url)?,
+        })
+    }
+
+    /// Builds `/api/v4/projects/{owner%2Fname}/...` so nested groups in `owner` stay one segment.
+    fn project_url(&self, repo: &Repository, path: &[&str]) -> Result<Url> {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .map_err(|_| format!("Not a valid GitLab url: {}", self.url))?
+            .pop_if_empty()
+            .extend(["api", "v4", "projects"])
+            .push(&format!("{}/{}", repo.owner, repo.name))
+            .extend(path);
+        Ok(url)
+    }
+
+    /// Fetches one page, along with the next page number from `X-Next-Page`.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        url: Url,
+        query: &[(&str, String)],
+        page: usize,
+        limit: usize,
+    ) -> Result<(T, Option<usize>)> {
+        let response = self
+            .client
+            .get(url)
+            .query(query)
+            .query(&[("page", page), ("per_page", limit)])
+            .send()
+            .await?
+            .error_for_status()?;
+        let next_page = response
+            .headers()
+            .get("X-Next-Page")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok());
+        Ok((response.json().await?, next_page))
+    }
+
+    async fn fetch_reviews(
+        &self,
+        repo: &Repository,
+        merge_request: &GitLabMergeRequest,
+    ) -> Result<Vec<PullReview>> {
+        let iid = merge_request.iid.to_string();
+
+        let url = self.project_url(repo, &["merge_requests", &iid, "approvals"])?;
+        let (approvals, _): (GitLabApprovals, _) = self.get(url, &[], 1, NOTES_PAGE_LIMIT).await?;
+
+        let mut discussions: Vec<GitLabDiscussion> = vec![];
+        let mut page = Some(1);
+        while let Some(current) = page {
+            let url = self.project_url(repo, &["merge_requests", &iid, "discussions"])?;
+            let (page_discussions, next_page): (Vec<GitLabDiscussion>, _) =
+                self.get(url, &[], current, NOTES_PAGE_LIMIT).await?;
+            discussions.extend(page_discussions);
+            page = next_page;
+        }
+
+        // Every note counts, replies included, like the comments of a Gitea review; the author's
+        // own notes are not review feedback.
+        let mut commenters: Vec<ForgeUser> = vec![];
+        let mut comments_count: HashMap<String, usize> = HashMap::new();
+        for note in discussions.iter().flat_map(|d| &d.notes) {
+            if note.system || note.author.username == merge_request.author.username {
+                continue;
+            }
+            let count = comments_count
+                .entry(note.author.username.clone())
+                .or_default();
+            if *count == 0 {
+                commenters.push(gitlab_to_forge_user(&note.author));
+            }
+            *count += 1;
+        }
+
+        let mut reviews = commenters
+            .into_iter()
+            .map(|user| {
+                let count = comments_count[&user.login];
+                PullReview::new(Some(user), ReviewState::Comment, count)
+            })
+            .collect::<Vec<_>>();
+        reviews.extend(approvals.approved_by.iter().map(|approver| {
+            PullReview::new(
+                Some(gitlab_to_forge_user(&approver.user)),
+                ReviewState::Approved,
+                0,
+            )
+        }));
+        Ok(reviews)
+    }
+}
+
+impl Forge for GitLabForge {
+    async fn fetch_pull_requests<'a>(
+        &self,
+        repo: &Repository,
+        since: &DateTime<FixedOffset>,
+        mut cb: PageProgress<'a>,
+    ) -> Result<Vec<PullRequest>> {
+        fn datetime_more_since(
+            datetime: &Option<DateTime<FixedOffset>>,
+            since: &DateTime<FixedOffset>,
+        ) -> bool {
+            datetime.is_some_and(|datetime| datetime >= *since)
+        }
+
+        let query = [
+            ("state", "all".to_string()),
+            ("order_by", "updated_at".to_string()),
+            ("sort", "desc".to_string()),
+            ("updated_after", since.to_rfc3339()),
+        ];
+        let mut page = Some(1);
+        let mut pull_requests: Vec<PullRequest> = vec![];
+
+        while let Some(current) = page {
+            cb(current as i64);
+            let url = self.project_url(repo, &["merge_requests"])?;
+            let (gitlab_merge_requests, next_page): (Vec<GitLabMergeRequest>, _) =
+                self.get(url, &query, current, PAGE_LIMIT).await?;
+            let gitlab_merge_requests = gitlab_merge_requests
+                .into_iter()
+                .filter(|mr| {
+                    datetime_more_since(&Some(mr.created_at), since)
+                        || datetime_more_since(&mr.merged_at, since)
+                        || datetime_more_since(&mr.closed_at, since)
+                })
+                .collect::<Vec<_>>();
+
+            let gitlab_reviews = futures::future::try_join_all(
+                gitlab_merge_requests
+                    .iter()
+                    .map(|mr| self.fetch_reviews(repo, mr))
+                    .collect::<Vec<_>>(),
+            )
+            .await?;
+
+            for (merge_request, reviews) in gitlab_merge_requests.into_iter().zip(gitlab_reviews) {
+                // Merged requests keep `closed_at` empty on GitLab, the analyzer expects both.
+                let closed_at = merge_request.closed_at.or(merge_request.merged_at);
+                pull_requests.push(PullRequest::new(
+                    merge_request.iid,
+                    gitlab_to_forge_user(&merge_request.author),
+                    merge_request.created_at,
+                    merge_request.merged_at,
+                    closed_at,
+                    reviews,
+                ));
+            }
+            page = next_page;
+        }
+        Ok(pull_requests)
+    }
+}
+
+fn gitlab_to_forge_user(user: &GitLabUser) -> ForgeUser {
+    ForgeUser::new(&user.username, user.public_email.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ForgeKind;
+    use crate::test_support::repository;
+    use mockito::{Matcher, Server};
+
+    fn merge_request(iid: i64, author: &str) -> String {
+        format!(
+            r#"{{"iid":{iid},"author":{{"username":"{author}"}},"created_at":"2025-01-02T10:00:00Z","merged_at":"2025-01-03T10:00:00Z","closed_at":null}}"#
+        )
+    }
+
+    fn note(author: &str, system: bool) -> String {
+        format!(r#"{{"author":{{"username":"{author}"}},"system":{system}}}"#)
+    }
+
+    #[tokio::test]
+    async fn fetches_pages_approvals_and_discussion_replies() {
+        let mut server = Server::new_async().await;
+        let projects = "/api/v4/projects/group%2Fapp/merge_requests";
+        let first_page = server
+            .mock("GET", projects)
+            .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+            .with_header("X-Next-Page", "2")
+            .with_body(format!("[{}]", merge_request(1, "alice")))
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", projects)
+            .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_header("X-Next-Page", "")
+            .with_body(format!("[{}]", merge_request(2, "bob")))
+            .create_async()
+            .await;
+        for iid in [1, 2] {
+            let approver = if iid == 1 { "bob" } else { "alice" };
+            server
+                .mock("GET", format!("{projects}/{iid}/approvals").as_str())
+                .match_query(Matcher::Any)
+                .with_body(format!(
+                    r#"{{"approved_by":[{{"user":{{"username":"{approver}","public_email":"{approver}@example.com"}}}}]}}"#
+                ))
+                .create_async()
+                .await;
+        }
+        server
+            .mock("GET", format!("{projects}/1/discussions").as_str())
+            .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+            .with_header("X-Next-Page", "2")
+            .with_body(format!(
+                "[{{\"notes\":[{},{},{}]}}]",
+                note("bob", false),
+                note("alice", false),
+                note("bob", false)
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("{projects}/1/discussions").as_str())
+            .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_body(format!(
+                "[{{\"notes\":[{},{}]}}]",
+                note("carol", false),
+                note("bob", true)
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("{projects}/2/discussions").as_str())
+            .match_query(Matcher::Any)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let repo = Repository {
+            forge: ForgeKind::GitLab,
+            ..repository("app")
+        };
+        let forge = GitLabForge::new(&server.url(), None).unwrap();
+        let since = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap();
+        let mut pages = vec![];
+        let pull_requests = forge
+            .fetch_pull_requests(&repo, &since, Box::new(|page| pages.push(page)))
+            .await
+            .unwrap();
+
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+        assert_eq!(pages, vec![1, 2]);
+        assert_eq!(
+            pull_requests.iter().map(|pr| pr.number).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        // Merged requests are closed too.
+        assert!(pull_requests[0].closed_at.is_some());
+
+        let reviews = &pull_requests[0].reviews;
+        let summary = reviews
+            .iter()
+            .map(|review| {
+                let author = review.author.as_ref().unwrap();
+                (author.login.as_str(), review.state, review.comments_count)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("bob", ReviewState::Comment, 2),
+                ("carol", ReviewState::Comment, 1),
+                ("bob", ReviewState::Approved, 0),
+            ]
+        );
+        assert_eq!(
+            reviews[2].author.as_ref().unwrap().email.as_deref(),
+            Some("bob@example.com")
+        );
+        assert!(pull_requests[1]
+            .reviews
+            .iter()
+            .all(|review| review.state == ReviewState::Approved));
+    }
+}
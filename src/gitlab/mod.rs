@@ -0,0 +1,3 @@
+pub mod merge_request;
+
+pub use merge_request::GitLabForge;
@@ -1,94 +1,470 @@
 mod analyze;
+mod forge;
 mod git;
 mod gitea;
+mod github;
+mod gitlab;
 mod model;
 mod report;
+mod snapshot;
+#[cfg(test)]
+mod test_support;
 mod utils;
 
 use crate::analyze::analyzer::Analyzer;
-use crate::analyze::DataAnalysis;
-use crate::git::{Commit, GitCommitRepository, GitRepository};
-use crate::gitea::pull_request::GiteaPullRequester;
-use crate::gitea::PullRequest;
+use crate::analyze::hotspots::HotspotAnalyzer;
+use crate::analyze::knowledge::KnowledgeAnalyzer;
+use crate::analyze::{DataAnalysis, HotspotThresholds, KnowledgeThresholds, SprintsAnalyzed};
+use crate::forge::{Forge, ForgeClient, PullRequest};
+use crate::git::{
+    partial, CacheMode, Commit, CommitDate, CommitQuery, CommitStatsCache, GitCommitRepository,
+    GitOwnershipRepository, GitRepository, Ownership,
+};
+use crate::gitea::GiteaForge;
+use crate::github::GitHubForge;
+use crate::gitlab::GitLabForge;
+use crate::report::csv::CsvReport;
+use crate::report::html::HtmlReport;
+use crate::report::i18n::Lang;
+use crate::report::json::JsonReport;
 use crate::report::markdown::MarkdownReport;
+use crate::snapshot::Snapshot;
 use crate::utils::{MultiProgressNew, ProgressStyleTemplate};
 use chrono::{DateTime, FixedOffset};
-use clap::Parser;
-use futures::{future, FutureExt};
-use gitea_sdk::{Auth, Client};
-use indicatif::{MultiProgress, ProgressBar, TermLike};
+use chrono_tz::Tz;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use futures::{future, stream, StreamExt};
+use indicatif::{MultiProgress, ProgressBar};
 use itertools::Itertools;
-use model::{Repository, Result, Sprint, User};
-use std::error::Error;
-use std::future::Future;
-use std::io::Write;
+use model::{
+    CoAuthorCredit, CommitType, Error, ForgeKind, Language, MergePolicy, Repository, Result,
+    Sprint, User,
+};
+use std::cell::Cell;
+use std::num::NonZeroUsize;
+use std::process;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 #[derive(Parser, Debug, Clone)]
-struct Args {
-    #[arg(long = "repos", default_value = "repositories.json")]
-    repos_path: String,
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Fetch repositories, analyze sprints and write reports (default).
+    Run(RunArgs),
+    /// Fetch repositories and store the collected data to a snapshot.
+    Fetch(FetchSnapshotArgs),
+    /// Analyze sprints from a snapshot and print a summary.
+    Analyze(SnapshotArgs),
+    /// Write reports from a snapshot.
+    Report(ReportSnapshotArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+struct ConfigArgs {
     #[arg(long = "sprints", default_value = "sprints.json")]
     sprints_path: String,
     #[arg(long = "users", default_value = "users.json")]
     users_path: String,
+    /// Maps file extensions to languages of the code breakdown, on top of the builtin map.
+    #[arg(long = "languages")]
+    languages_path: Option<String>,
+    /// Maps Conventional Commits types to the types of the commit breakdown, on top of the
+    /// builtin map.
+    #[arg(long = "commit_types")]
+    commit_types_path: Option<String>,
+    /// IANA timezone of sprint bounds without an offset, `timezone` of a sprint overrides it.
+    #[arg(long = "timezone", default_value = "UTC")]
+    timezone: Tz,
+    /// How the lines of commits with `Co-authored-by` trailers are credited.
+    #[arg(long = "co_author_credit", value_enum, default_value_t = CoAuthorCredit::Full)]
+    co_author_credit: CoAuthorCredit,
+}
+
+#[derive(Args, Debug, Clone)]
+struct FetchArgs {
+    #[arg(long = "repos", default_value = "repositories.json")]
+    repos_path: String,
     #[arg(long = "cache_path", default_value = "repos")]
     repos_cache_path: String,
+    #[arg(long = "cache_mode", value_enum, default_value_t = CacheMode::Worktree)]
+    cache_mode: CacheMode,
     #[arg(long = "gitea_url")]
-    gitea_url: String,
+    gitea_url: Option<String>,
     #[arg(long = "gitea_token")]
-    gitea_token: String,
+    gitea_token: Option<String>,
+    #[arg(long = "github_url", default_value = "https://api.github.com")]
+    github_url: String,
+    #[arg(long = "github_token")]
+    github_token: Option<String>,
+    #[arg(long = "gitlab_url", default_value = "https://gitlab.com")]
+    gitlab_url: String,
+    #[arg(long = "gitlab_token")]
+    gitlab_token: Option<String>,
+    #[arg(long = "on_repo_error", value_enum, default_value_t = FailurePolicy::Skip)]
+    on_repo_error: FailurePolicy,
+    #[arg(long = "jobs", default_value = "4")]
+    jobs: NonZeroUsize,
+    /// Stop reading git history after this many consecutive commits older than the first
+    /// sprint, 0 reads the whole history.
+    #[arg(long = "stop_after_old_commits", default_value = "100")]
+    stop_after_old_commits: usize,
+    /// How merge commits are counted, `merges` in `repositories.json` overrides it per
+    /// repository.
+    #[arg(long = "merges", value_enum, default_value_t = MergePolicy::Full)]
+    merges: MergePolicy,
+    /// Which commit time places a commit in a sprint.
+    #[arg(long = "commit_date", value_enum, default_value_t = CommitDate::Author)]
+    commit_date: CommitDate,
+    /// Enables the churn metrics, lines changed again within this many days after they were
+    /// written count as rework. Every changed line is blamed, so reading history gets slower.
+    #[arg(long = "churn_days")]
+    churn_days: Option<u32>,
+    /// Counts only paths matching these `.gitignore`-style globs in line stats, repeatable.
+    #[arg(long = "include")]
+    include: Vec<String>,
+    /// Leaves paths matching these `.gitignore`-style globs out of line stats, repeatable.
+    /// Files marked `linguist-generated` or `linguist-vendored` are left out as well.
+    #[arg(long = "exclude")]
+    exclude: Vec<String>,
+    /// Blames every file of the configured branch at the end of each sprint for the knowledge
+    /// distribution, reading history gets much slower.
+    #[arg(long = "ownership")]
+    ownership: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+struct ReportArgs {
+    #[arg(long = "format", value_enum, default_value_t = ReportFormat::Markdown)]
+    format: ReportFormat,
+    /// Language of the labels in the Markdown and HTML reports. JSON and CSV are read by
+    /// programs, their keys and column names stay in English whatever the language.
+    #[arg(long = "lang", value_enum, default_value_t = Lang::Ru)]
+    lang: Lang,
+    /// Most changed files, directories and coupled file pairs listed per repository.
+    #[arg(long = "hotspots_top", default_value_t = 10)]
+    hotspots_top: usize,
+    /// Fewest commits two files change together in to count as coupled.
+    #[arg(long = "coupling_min_commits", default_value_t = 3)]
+    coupling_min_commits: usize,
+    /// Lowest share, in percent, of shared commits in the average commits of the two files.
+    #[arg(long = "coupling_min_degree", default_value_t = 50)]
+    coupling_min_degree: usize,
+    /// Commits changing more files are left out of the coupling.
+    #[arg(long = "coupling_max_files", default_value_t = 30)]
+    coupling_max_files: usize,
+    /// Share of the lines of a module, in percent, one person has to exceed to be flagged as
+    /// its only owner. Needs `--ownership` when fetching.
+    #[arg(long = "bus_factor_share", default_value_t = 80)]
+    bus_factor_share: usize,
+    /// Modules with fewer blamed lines are not flagged.
+    #[arg(long = "bus_factor_min_lines", default_value_t = 100)]
+    bus_factor_min_lines: usize,
+    /// Directory depth of the modules of the knowledge distribution.
+    #[arg(long = "module_depth", default_value_t = 2)]
+    module_depth: usize,
+}
+
+#[derive(Args, Debug, Clone)]
+struct RunArgs {
+    #[command(flatten)]
+    configs: ConfigArgs,
+    #[command(flatten)]
+    fetch: FetchArgs,
+    #[command(flatten)]
+    report: ReportArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+struct FetchSnapshotArgs {
+    #[command(flatten)]
+    configs: ConfigArgs,
+    #[command(flatten)]
+    fetch: FetchArgs,
+    #[arg(long = "snapshot", default_value = "snapshot.json")]
+    snapshot_path: String,
+}
+
+#[derive(Args, Debug, Clone)]
+struct SnapshotArgs {
+    #[command(flatten)]
+    configs: ConfigArgs,
+    #[arg(long = "snapshot", default_value = "snapshot.json")]
+    snapshot_path: String,
+}
+
+#[derive(Args, Debug, Clone)]
+struct ReportSnapshotArgs {
+    #[command(flatten)]
+    snapshot: SnapshotArgs,
+    #[command(flatten)]
+    report: ReportArgs,
+}
+
+/// File format of the per-team reports.
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+enum ReportFormat {
+    /// `{team}.md` with a table per sprint.
+    Markdown,
+    /// `{team}.json` following `schema/report.schema.json`.
+    Json,
+    /// `{team}.csv` with a row per sprint and user and a column per metric, named in English.
+    Csv,
+    /// `{team}.long.csv` with a row per sprint, user and metric.
+    CsvLong,
+    /// `{team}.html`, a self-contained page with charts.
+    Html,
+}
+
+/// What to do when fetching a single repository fails.
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+enum FailurePolicy {
+    /// Leave the repository out of the analysis and list it as failed.
+    Skip,
+    /// Stop the whole run.
+    Abort,
 }
 
 #[tokio::main]
 async fn main() {
-    let args = Args::parse();
-    run(&args).await.unwrap()
+    let cli = Cli::parse();
+    let result = match cli.command.unwrap_or(Command::Run(cli.run)) {
+        Command::Run(args) => run(&args).await,
+        Command::Fetch(args) => fetch_snapshot(&args).await,
+        Command::Analyze(args) => analyze_snapshot(&args).await,
+        Command::Report(args) => report_snapshot(&args).await,
+    };
+    if let Err(err) = result {
+        eprintln!("❌ {err}");
+        process::exit(1);
+    }
 }
 
-async fn run(args: &Args) -> Result<()> {
-    let (users, sprints, repos) = tokio::spawn(parse_configs(args.clone())).await?;
+async fn run(args: &RunArgs) -> Result<()> {
+    let repos_path = Some(args.fetch.repos_path.as_str());
+    let (users, sprints, repos, languages, commit_types) =
+        parse_configs(&args.configs, repos_path).await?;
+    let (min_since, max_until) = calc_period(&sprints)?;
+    let data_analysis = DataAnalysis::new(
+        users.clone(),
+        sprints,
+        repos,
+        languages,
+        commit_types,
+        args.configs.co_author_credit,
+    );
+    let data_analysis = fetch(&args.fetch, data_analysis, &min_since, &max_until).await?;
+    report(&args.report, &data_analysis, &users)?;
+    print_unmatched_authors(&data_analysis);
+    print_failures(&data_analysis);
+    Ok(())
+}
 
-    let min_since = calc_min_since(&sprints);
-    let data_analysis = {
-        let analyzer = DataAnalysis::new(users.clone(), sprints.clone(), repos.clone());
-        Arc::new(Mutex::new(analyzer))
+async fn fetch_snapshot(args: &FetchSnapshotArgs) -> Result<()> {
+    let repos_path = Some(args.fetch.repos_path.as_str());
+    let (users, sprints, repos, languages, commit_types) =
+        parse_configs(&args.configs, repos_path).await?;
+    let (min_since, max_until) = calc_period(&sprints)?;
+    let credit = args.configs.co_author_credit;
+    let data_analysis = DataAnalysis::new(users, sprints, repos, languages, commit_types, credit);
+    let data_analysis = fetch(&args.fetch, data_analysis, &min_since, &max_until).await?;
+    Snapshot::new(&data_analysis, min_since, max_until).save(&args.snapshot_path)?;
+    print_unmatched_authors(&data_analysis);
+    print_failures(&data_analysis);
+    Ok(())
+}
+
+async fn analyze_snapshot(args: &SnapshotArgs) -> Result<()> {
+    let data_analysis = load_snapshot(args).await?;
+    print_summary(&data_analysis.analyze_sprints());
+    print_unmatched_authors(&data_analysis);
+    print_failures(&data_analysis);
+    Ok(())
+}
+
+async fn report_snapshot(args: &ReportSnapshotArgs) -> Result<()> {
+    let data_analysis = load_snapshot(&args.snapshot).await?;
+    report(&args.report, &data_analysis, &data_analysis.users)?;
+    print_unmatched_authors(&data_analysis);
+    print_failures(&data_analysis);
+    Ok(())
+}
+
+async fn fetch(
+    args: &FetchArgs,
+    data_analysis: DataAnalysis,
+    min_since: &DateTime<FixedOffset>,
+    max_until: &DateTime<FixedOffset>,
+) -> Result<DataAnalysis> {
+    let repos = data_analysis.repos.clone();
+    let ownership_at = match args.ownership {
+        true => data_analysis.sprints.iter().map(|sprint| sprint.until).collect(),
+        false => vec![],
     };
+    let ownership_at = &ownership_at;
+    let data_analysis = Arc::new(Mutex::new(data_analysis));
 
-    for repo in &repos {
-        let (commits, pull_requests) = repo_fetch(repo, args, &min_since).await;
-        data_analysis
-            .lock()
-            .await
-            .insert_commits(repo, commits.clone());
-        data_analysis
-            .lock()
-            .await
-            .insert_pull_request(repo, pull_requests.clone());
+    let multi_progress = &MultiProgress::default();
+    let mut fetches = stream::iter(&repos)
+        .map(|repo| async move {
+            let result =
+                repo_fetch(repo, args, min_since, max_until, ownership_at, multi_progress).await;
+            (repo, result)
+        })
+        .buffer_unordered(args.jobs.get());
+    while let Some((repo, result)) = fetches.next().await {
+        match result {
+            Ok((commits, ownership, pull_requests)) => {
+                let mut data_analysis = data_analysis.lock().await;
+                data_analysis.insert_commits(repo, commits);
+                data_analysis.insert_ownership(repo, ownership);
+                data_analysis.insert_pull_request(repo, pull_requests);
+            }
+            Err(err) if args.on_repo_error == FailurePolicy::Skip => {
+                data_analysis.lock().await.insert_failure(repo, err);
+            }
+            Err(err) => return Err(err),
+        }
     }
 
-    let analyze = data_analysis.lock().await.analyze_sprints();
-    for team in find_teams(&users) {
-        analyze.report_create(&team);
+    let data_analysis = data_analysis.lock().await.clone();
+    Ok(data_analysis)
+}
+
+async fn load_snapshot(args: &SnapshotArgs) -> Result<DataAnalysis> {
+    let (users, sprints, _, languages, commit_types) = parse_configs(&args.configs, None).await?;
+    let snapshot = Snapshot::load(&args.snapshot_path)?;
+    for sprint in sprints.iter().filter(|sprint| sprint.since < snapshot.since) {
+        eprintln!(
+            "⚠️ Sprint `{}` starts before the snapshot data ({}), its metrics are incomplete",
+            sprint.name,
+            snapshot.since.format("%d.%m.%Y"),
+        );
     }
+    for sprint in sprints.iter().filter(|sprint| sprint.until > snapshot.until) {
+        eprintln!(
+            "⚠️ Sprint `{}` ends after the snapshot data ({}), its metrics are incomplete",
+            sprint.name,
+            snapshot.until.format("%d.%m.%Y"),
+        );
+    }
+    let credit = args.configs.co_author_credit;
+    Ok(snapshot.into_analysis(users, sprints, languages, commit_types, credit))
+}
 
+fn report(args: &ReportArgs, data_analysis: &DataAnalysis, users: &[User]) -> Result<()> {
+    let analyze = data_analysis.analyze_sprints();
+    let failures = &data_analysis.failures;
+    let unmatched = &data_analysis.unmatched_authors();
+    let hotspot_thresholds = HotspotThresholds::new(
+        args.hotspots_top,
+        args.coupling_min_commits,
+        args.coupling_min_degree,
+        args.coupling_max_files,
+    );
+    let knowledge_thresholds = KnowledgeThresholds::new(
+        args.bus_factor_share,
+        args.bus_factor_min_lines,
+        args.module_depth,
+    );
+    for team in find_teams(users) {
+        let hotspots = &data_analysis.analyze_hotspots(&team, &hotspot_thresholds);
+        let knowledge = &data_analysis.analyze_knowledge(&team, &knowledge_thresholds);
+        match args.format {
+            ReportFormat::Markdown => MarkdownReport::report_create(
+                &analyze, &team, args.lang, failures, unmatched, hotspots, knowledge,
+            )?,
+            ReportFormat::Json => JsonReport::report_create(
+                &analyze, &team, failures, unmatched, hotspots, knowledge,
+            )?,
+            ReportFormat::Csv => CsvReport::report_create(&analyze, &team)?,
+            ReportFormat::CsvLong => CsvReport::long_report_create(&analyze, &team)?,
+            ReportFormat::Html => HtmlReport::report_create(
+                &analyze, &team, args.lang, failures, unmatched, hotspots, knowledge,
+            )?,
+        }
+    }
     Ok(())
 }
 
-async fn parse_configs(args: Args) -> (Vec<User>, Vec<Sprint>, Vec<Repository>) {
-    async fn parse_config<T, F>(path: &str, pb: &ProgressBar, parser: F) -> Vec<T>
+fn print_summary(analyze: &SprintsAnalyzed) {
+    for (sprint, users) in analyze {
+        println!(
+            "# {} ({} - {})",
+            sprint.name,
+            sprint.since.format("%d.%m.%Y"),
+            sprint.until.format("%d.%m.%Y"),
+        );
+        for (user, data) in users {
+            println!(
+                "  {}: {} commits (+{} / -{}), {} PR created, {} PR merged, {} reviews",
+                user.username,
+                data.commits.commits,
+                data.commits.insertions,
+                data.commits.deletions,
+                data.pull_requests.create_pull_requests,
+                data.pull_requests.merged_pull_requests,
+                data.pull_requests.approver_conducted,
+            );
+        }
+    }
+}
+
+fn print_unmatched_authors(data_analysis: &DataAnalysis) {
+    let unmatched = data_analysis.unmatched_authors();
+    if !unmatched.is_empty() {
+        eprintln!("⚠️ Commit authors matching no user:");
+        for author in &unmatched {
+            eprintln!(
+                "  - {}: {} commits, {} lines",
+                author.email, author.commits, author.change_lines
+            );
+        }
+    }
+}
+
+fn print_failures(data_analysis: &DataAnalysis) {
+    if !data_analysis.failures.is_empty() {
+        eprintln!("❌ Failed repositories:");
+        for (repo, err) in &data_analysis.failures {
+            eprintln!("  - {}: {}", repo.name, err);
+        }
+    }
+}
+
+type Configs = (
+    Vec<User>,
+    Vec<Sprint>,
+    Vec<Repository>,
+    Vec<Language>,
+    Vec<CommitType>,
+);
+
+async fn parse_configs(
+    args: &ConfigArgs,
+    repos_path: Option<&str>,
+) -> Result<Configs> {
+    async fn parse_config<T, F>(path: &str, pb: &ProgressBar, parser: F) -> Result<Vec<T>>
     where
         F: FnOnce(&str) -> Result<Vec<T>>,
     {
         pb.set_message(format!("Read file `{}` ...", path));
-        let vec = parser(path).unwrap();
+        let vec = parser(path).inspect_err(|err| {
+            pb.finish_with_message(format!("❌ Failed parsing file `{}`: {}", path, err))
+        })?;
         pb.finish_with_message(format!(
             "✅ Completed parsing file `{}` (find {} elements)",
             path,
             vec.len()
         ));
-        vec
+        Ok(vec)
     }
 
     let multi_progress = MultiProgress::default();
@@ -100,98 +476,175 @@ async fn parse_configs(args: Args) -> (Vec<User>, Vec<Sprint>, Vec<Repository>)
         ProgressBar::no_length(),
         ProgressStyleTemplate::only_message(),
     );
-    let repos_pb = multi_progress.add_with_style(
-        ProgressBar::no_length(),
-        ProgressStyleTemplate::only_message(),
-    );
+    let repos = async {
+        let Some(repos_path) = repos_path else {
+            return Ok(vec![]);
+        };
+        let repos_pb = multi_progress.add_with_style(
+            ProgressBar::no_length(),
+            ProgressStyleTemplate::only_message(),
+        );
+        parse_config(repos_path, &repos_pb, Repository::from_config).await
+    };
+    let languages = async {
+        let Some(languages_path) = &args.languages_path else {
+            return Ok(vec![]);
+        };
+        let languages_pb = multi_progress.add_with_style(
+            ProgressBar::no_length(),
+            ProgressStyleTemplate::only_message(),
+        );
+        parse_config(languages_path, &languages_pb, Language::from_config).await
+    };
+    let commit_types = async {
+        let Some(commit_types_path) = &args.commit_types_path else {
+            return Ok(vec![]);
+        };
+        let commit_types_pb = multi_progress.add_with_style(
+            ProgressBar::no_length(),
+            ProgressStyleTemplate::only_message(),
+        );
+        parse_config(commit_types_path, &commit_types_pb, CommitType::from_config).await
+    };
 
-    futures::join!(
+    let (users, sprints, repos, languages, commit_types) = futures::join!(
         parse_config(&args.users_path, &users_pb, User::from_config),
-        parse_config(&args.sprints_path, &sprints_pb, Sprint::from_config),
-        parse_config(&args.repos_path, &repos_pb, Repository::from_config),
-    )
+        parse_config(&args.sprints_path, &sprints_pb, |path| {
+            Sprint::from_config(path, args.timezone)
+        }),
+        repos,
+        languages,
+        commit_types,
+    );
+    Ok((users?, sprints?, repos?, languages?, commit_types?))
 }
 
 async fn repo_fetch(
     repo: &Repository,
-    args: &Args,
+    args: &FetchArgs,
     min_since: &DateTime<FixedOffset>,
-) -> (Vec<Commit>, Vec<PullRequest>) {
-    let mut multi_progress = MultiProgress::default();
-    multi_progress.println(&format!("# {}", repo.name)).unwrap();
-
-    let repo_pb = multi_progress.add_with_style(
+    max_until: &DateTime<FixedOffset>,
+    ownership_at: &[DateTime<FixedOffset>],
+    multi_progress: &MultiProgress,
+) -> Result<(Vec<Commit>, Vec<Ownership>, Vec<PullRequest>)> {
+    // Bars of one repository are inserted after each other to stay grouped under its title
+    // while other repositories are fetched at the same time.
+    let title_pb = multi_progress.add_with_style(
+        ProgressBar::no_length(),
+        ProgressStyleTemplate::only_message(),
+    );
+    title_pb.finish_with_message(format!("# {}", repo.name));
+    let repo_pb = multi_progress.insert_after_with_style(
+        &title_pb,
         ProgressBar::no_length(),
         ProgressStyleTemplate::percent_bar(),
     );
     repo_pb.set_message("Fetching...");
-    let commit_pb = multi_progress.add_with_style(
+    let commit_pb = multi_progress.insert_after_with_style(
+        &repo_pb,
         ProgressBar::new_spinner(),
         ProgressStyleTemplate::only_message(),
     );
     commit_pb.set_message("Waiting clone");
-    let pull_request_pb = multi_progress.add_with_style(
+    let pull_request_pb = multi_progress.insert_after_with_style(
+        &commit_pb,
         ProgressBar::new_spinner(),
         ProgressStyleTemplate::only_message(),
     );
-    pull_request_pb.set_message("Waiting Gitea");
+    pull_request_pb.set_message(format!("Waiting {}", repo.forge.title()));
 
-    let gitea_client = Client::new(&args.gitea_url, Auth::Token(&args.gitea_token));
+    let forge_client = forge_client(repo, args)?;
     let repos_cache_path = args.repos_cache_path.to_string();
-    let (commits, pull_requests) = future::join(
-        tokio::spawn(git_fetch(
-            repo.clone(),
-            repos_cache_path,
-            min_since.clone(),
-            repo_pb,
-            commit_pb,
-        )),
-        tokio::spawn(gitea_fetch(
-            gitea_client,
+    let cache_mode = args.cache_mode;
+    let git_repo = repo.clone();
+    let ownership_at = ownership_at.to_vec();
+    let query = CommitQuery {
+        date: args.commit_date,
+        churn_days: args.churn_days,
+        ..CommitQuery::new(
+            [std::slice::from_ref(&repo.branch), &repo.branches].concat(),
+            *min_since,
+            *max_until,
+            args.stop_after_old_commits,
+            repo.merges.unwrap_or(args.merges),
+            [args.include.as_slice(), &repo.include].concat(),
+            [args.exclude.as_slice(), &repo.exclude].concat(),
+        )
+    };
+    let (git_data, pull_requests) = future::join(
+        tokio::task::spawn_blocking(move || {
+            git_fetch(
+                git_repo,
+                repos_cache_path,
+                cache_mode,
+                query,
+                ownership_at,
+                repo_pb,
+                commit_pb,
+            )
+        }),
+        tokio::spawn(forge_fetch(
+            forge_client,
             repo.clone(),
-            min_since.clone(),
+            *min_since,
             pull_request_pb,
         )),
     )
     .await;
-    (commits.unwrap(), pull_requests.unwrap())
+    let (commits, ownership) = git_data.map_err(|e| Error::task(&repo.name, e))??;
+    let pull_requests = pull_requests.map_err(|e| Error::task(&repo.name, e))?;
+    Ok((commits, ownership, pull_requests?))
 }
 
-async fn git_fetch(
+fn git_fetch(
     repo: Repository,
     repo_dir_path: String,
-    min_since: DateTime<FixedOffset>,
+    cache_mode: CacheMode,
+    query: CommitQuery,
+    ownership_at: Vec<DateTime<FixedOffset>>,
     fetch_pb: ProgressBar,
     commits_pb: ProgressBar,
-) -> Vec<Commit> {
-    let mut one_call_progress = false;
+) -> Result<(Vec<Commit>, Vec<Ownership>)> {
+    let one_call_progress = Cell::new(false);
     let progress_bg = fetch_pb.clone();
     let progress = |is_clone: bool| {
+        let one_call_progress = &one_call_progress;
         move |current, total| {
-            one_call_progress = true;
+            one_call_progress.set(true);
             progress_bg.set_message(if is_clone { "Cloning" } else { "Pulling" });
             progress_bg.set_position(current as u64);
             progress_bg.set_length(total as u64);
         }
     };
 
-    let is_clone = !repo.repo_exists(&repo_dir_path);
+    let is_clone = !repo.repo_exists(&repo_dir_path, cache_mode);
     fetch_pb.set_message(if is_clone {
         "Cloning ..."
     } else {
         "Pulling ..."
     });
     let git_repo = if is_clone {
-        repo.repo_clone(&repo_dir_path, Box::new(progress(is_clone)))
-            .unwrap()
+        repo.repo_clone(&repo_dir_path, cache_mode, Box::new(progress(is_clone)))
     } else {
-        repo.repo_pull(&repo_dir_path, Box::new(progress(is_clone)))
-            .unwrap()
+        repo.repo_pull(&repo_dir_path, cache_mode, Box::new(progress(is_clone)))
+            .map(|git_repo| {
+                let fallback = (repo.partial_clone && !partial::is_partial(&git_repo))
+                    .then(|| "the cache holds a full clone".to_string());
+                (git_repo, fallback)
+            })
     };
 
     fetch_pb.reset();
     fetch_pb.set_style(ProgressStyleTemplate::only_message());
-    if one_call_progress {
+    let (git_repo, fallback) = match git_repo {
+        Ok(result) => result,
+        Err(err) => {
+            fetch_pb.finish_with_message(format!("❌ {}", err.message()));
+            commits_pb.finish_with_message("Skipped");
+            return Err(Error::git(&repo.name, err));
+        }
+    };
+    if one_call_progress.get() {
         fetch_pb.finish_with_message(if is_clone {
             "✅ Cloned"
         } else {
@@ -204,46 +657,120 @@ async fn git_fetch(
             "Already up to date"
         });
     }
+    if let Some(reason) = fallback {
+        fetch_pb.println(format!(
+            "⚠️ Partial clone of `{}` is not available, a full clone is used: {reason}",
+            repo.name
+        ));
+    }
 
     commits_pb.set_message("Read git history ...");
-    let commits = git_repo.get_commits(&min_since).unwrap();
+    let fingerprint = match git_repo.diff_fingerprint(&query) {
+        Ok(fingerprint) => fingerprint,
+        Err(err) => {
+            commits_pb.finish_with_message(format!("❌ {}", err.message()));
+            return Err(Error::git(&repo.name, err));
+        }
+    };
+    let mut cache = CommitStatsCache::open(repo.stats_cache_path(&repo_dir_path), &fingerprint);
+    let cached = cache.len();
+    let (commits, scanned) = match git_repo.get_commits(&query, &mut cache) {
+        Ok(result) => result,
+        Err(err) => {
+            commits_pb.finish_with_message(format!("❌ {}", err.message()));
+            return Err(Error::git(&repo.name, err));
+        }
+    };
+    let diffed = cache.len() - cached;
+    if let Err(err) = cache.save() {
+        let message = format!("⚠️ Commit stats cache of `{}` not saved: {}", repo.name, err);
+        commits_pb.println(message);
+    }
+    let summary = format!(
+        "scanned {} commits, kept {}, {} diffed",
+        scanned,
+        commits.len(),
+        diffed
+    );
+    if ownership_at.is_empty() {
+        commits_pb.finish_with_message(format!("✅ Completed read git history ({summary})"));
+        return Ok((commits, vec![]));
+    }
+
+    commits_pb.set_message(format!("Blame files ... ({summary})"));
+    let ownership = match git_repo.get_ownership(&query, &ownership_at) {
+        Ok(ownership) => ownership,
+        Err(err) => {
+            commits_pb.finish_with_message(format!("❌ {}", err.message()));
+            return Err(Error::git(&repo.name, err));
+        }
+    };
     commits_pb.finish_with_message(format!(
-        "✅ Completed read git history (find {} commits)",
-        commits.len()
+        "✅ Completed read git history ({summary}, {} trees blamed)",
+        ownership.len()
     ));
-    commits
+    Ok((commits, ownership))
+}
+
+fn forge_client(repo: &Repository, args: &FetchArgs) -> Result<ForgeClient> {
+    let client = match repo.forge {
+        ForgeKind::Gitea => {
+            let (Some(url), Some(token)) = (&args.gitea_url, &args.gitea_token) else {
+                return Err(Error::forge(
+                    &repo.name,
+                    "`--gitea_url` and `--gitea_token` are required for Gitea repositories",
+                ));
+            };
+            ForgeClient::Gitea(GiteaForge::new(url, token))
+        }
+        ForgeKind::GitHub => {
+            let forge = GitHubForge::new(&args.github_url, args.github_token.as_deref());
+            ForgeClient::GitHub(forge.map_err(|e| Error::forge(&repo.name, e))?)
+        }
+        ForgeKind::GitLab => {
+            let forge = GitLabForge::new(&args.gitlab_url, args.gitlab_token.as_deref());
+            ForgeClient::GitLab(forge.map_err(|e| Error::forge(&repo.name, e))?)
+        }
+    };
+    Ok(client)
 }
 
-async fn gitea_fetch(
-    client: Client,
+async fn forge_fetch(
+    forge: ForgeClient,
     repo: Repository,
     min_since: DateTime<FixedOffset>,
     pb: ProgressBar,
-) -> Vec<PullRequest> {
+) -> Result<Vec<PullRequest>> {
     let progress_pb = pb.clone();
     let progress = move |page: i64| {
-        progress_pb.set_message(format!("Fetch pull requests (#{} page) ...", page + 1));
+        progress_pb.set_message(format!("Fetch pull requests (#{} page) ...", page));
     };
-    let pull_requests = repo
-        .fetch_pull_request(&client, &min_since, Box::new(progress))
+    let pull_requests = match forge
+        .fetch_pull_requests(&repo, &min_since, Box::new(progress))
         .await
-        .unwrap();
+    {
+        Ok(pull_requests) => pull_requests,
+        Err(err) => {
+            pb.finish_with_message(format!("❌ {}", err));
+            return Err(Error::forge(&repo.name, err));
+        }
+    };
     pb.finish_with_message(format!(
         "✅ Completed fetch pull requests (find {} pull requests)",
         pull_requests.len()
     ));
-    pull_requests
+    Ok(pull_requests)
 }
 
-fn calc_min_since(sprints: &Vec<Sprint>) -> DateTime<FixedOffset> {
-    sprints
-        .iter()
-        .min_by(|s1, s2| s1.since.cmp(&s2.since))
-        .unwrap()
-        .since
+fn calc_period(sprints: &[Sprint]) -> Result<(DateTime<FixedOffset>, DateTime<FixedOffset>)> {
+    let min_since = sprints.iter().map(|sprint| sprint.since).min();
+    let max_until = sprints.iter().map(|sprint| sprint.until).max();
+    min_since
+        .zip(max_until)
+        .ok_or_else(|| Error::analysis("No sprints to analyze"))
 }
 
-fn find_teams<'a>(users: &'a Vec<User>) -> Vec<String> {
+fn find_teams(users: &[User]) -> Vec<String> {
     users
         .iter()
         .flat_map(|u| u.teams.clone())
@@ -0,0 +1,10 @@
+use clap::ValueEnum;
+
+/// How the lines of a commit with `Co-authored-by` trailers are credited.
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, Hash, PartialEq)]
+pub enum CoAuthorCredit {
+    /// The author and every co-author get all the lines and files.
+    Full,
+    /// The lines and files are split equally, the author gets the remainder.
+    Split,
+}
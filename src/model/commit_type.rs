@@ -0,0 +1,82 @@
+use crate::model::{BoxError, Error, Result};
+use indexmap::IndexMap;
+use serde_json::{from_str, Value};
+use std::fs;
+
+/// Conventional Commits types grouped the way the reports break commits down, used when no
+/// config overrides them.
+const BUILTIN: [(&str, &[&str]); 5] = [
+    ("feat", &["feat", "feature"]),
+    ("fix", &["fix", "bugfix", "hotfix"]),
+    ("refactor", &["refactor", "perf", "style"]),
+    ("test", &["test", "tests"]),
+    (
+        "chore",
+        &["chore", "build", "ci", "docs", "revert", "release"],
+    ),
+];
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+pub struct CommitType {
+    pub name: String,
+    /// Lowercase types of commit headers, like `feat` of `feat(api): ...`.
+    pub prefixes: Vec<String>,
+}
+
+// Create
+impl CommitType {
+    pub fn from_config(path: &str) -> Result<Vec<Self>> {
+        let json_str = fs::read_to_string(path).map_err(|e| Error::config(path, e))?;
+        Self::parse(&json_str).map_err(|e| Error::config(path, e))
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        BUILTIN
+            .iter()
+            .map(|(name, prefixes)| Self::new(name, prefixes.to_vec()))
+            .collect()
+    }
+
+    fn new(name: impl ToString, prefixes: Vec<impl ToString>) -> Self {
+        Self {
+            name: name.to_string(),
+            prefixes: prefixes.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+// Matching
+impl CommitType {
+    /// The first commit type with the prefix, so configured types go before the builtin ones.
+    pub fn find<'a>(commit_types: &'a [Self], prefix: &str) -> Option<&'a Self> {
+        commit_types.iter().find(|commit_type| {
+            commit_type
+                .prefixes
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(prefix))
+        })
+    }
+}
+
+// Parser
+impl CommitType {
+    fn parse(json_str: &str) -> std::result::Result<Vec<Self>, BoxError> {
+        let elements: IndexMap<String, Value> = from_str(json_str)?;
+        let mut result = Vec::new();
+        for (name, details) in elements {
+            let prefixes = match details["prefixes"].as_array() {
+                Some(prefixes) => prefixes
+                    .iter()
+                    .map(|prefix| match prefix.as_str() {
+                        Some(prefix) => Ok(prefix.to_lowercase()),
+                        None => Err("Field 'prefixes' must contain strings".to_string()),
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?,
+                None => return Err("Not found 'prefixes' field".into()),
+            };
+            let new = Self::new(name, prefixes);
+            result.push(new);
+        }
+        Ok(result)
+    }
+}
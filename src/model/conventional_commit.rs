@@ -0,0 +1,101 @@
+/// Header of a commit message following Conventional Commits, `type(scope)!: description`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ConventionalCommit {
+    pub kind: String,
+    pub scope: Option<String>,
+    /// `!` in the header or a `BREAKING CHANGE` footer.
+    pub breaking: bool,
+}
+
+// Parser
+impl ConventionalCommit {
+    /// `None` for messages whose first line doesn't follow the format.
+    pub fn parse(message: &str) -> Option<Self> {
+        let mut lines = message.lines();
+        let (prefix, description) = lines.next()?.split_once(": ")?;
+        if description.trim().is_empty() {
+            return None;
+        }
+        let (prefix, breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => {
+                let scope = scope.strip_suffix(')')?;
+                if scope.is_empty() || scope.contains(['(', ')']) {
+                    return None;
+                }
+                (kind, Some(scope.to_string()))
+            }
+            None => (prefix, None),
+        };
+        let is_word = kind.starts_with(|c: char| c.is_ascii_alphabetic())
+            && kind
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_word {
+            return None;
+        }
+        let breaking = breaking
+            || lines.any(|line| {
+                line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+            });
+        Some(Self {
+            kind: kind.to_lowercase(),
+            scope,
+            breaking,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kind, scope and whether the change is breaking.
+    type Header<'a> = (&'a str, Option<&'a str>, bool);
+
+    #[test]
+    fn parses_headers_and_footers() {
+        let cases: &[(&str, Option<Header>)] = &[
+            ("feat(scope): x", Some(("feat", Some("scope"), false))),
+            ("feat!: x", Some(("feat", None, true))),
+            ("fix(api)!: x", Some(("fix", Some("api"), true))),
+            ("Docs: x", Some(("docs", None, false))),
+            ("build-ci_2: x", Some(("build-ci_2", None, false))),
+            ("fix: x\n\nBREAKING CHANGE: y", Some(("fix", None, true))),
+            ("fix: x\n\nBREAKING-CHANGE: y", Some(("fix", None, true))),
+            (
+                "fix: x\n\nMentions BREAKING CHANGE: y",
+                Some(("fix", None, false)),
+            ),
+            // Empty description.
+            ("feat: ", None),
+            ("feat:  \nbody", None),
+            ("feat:", None),
+            // Unbalanced, nested or empty parens.
+            ("feat(scope: x", None),
+            ("feat scope): x", None),
+            ("feat(a(b)): x", None),
+            ("feat(): x", None),
+            ("feat!(scope): x", None),
+            // Not conventional.
+            ("Merge branch 'main'", None),
+            ("Update README.md", None),
+            ("", None),
+            // Type with non-word characters.
+            ("fe@t: x", None),
+            ("feat fix: x", None),
+            ("1feat: x", None),
+        ];
+        for (message, expected) in cases {
+            let expected = expected.map(|(kind, scope, breaking)| ConventionalCommit {
+                kind: kind.to_string(),
+                scope: scope.map(str::to_string),
+                breaking,
+            });
+            assert_eq!(ConventionalCommit::parse(message), expected, "{message:?}");
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+pub type BoxError = Box<dyn StdError + Send + Sync>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// A configuration file can't be read or parsed.
+    Config { path: String, source: BoxError },
+    /// Cloning, pulling or reading the history of a repository failed.
+    Git {
+        repository: String,
+        source: git2::Error,
+    },
+    /// Fetching pull requests of a repository from its forge failed.
+    Forge {
+        repository: String,
+        source: BoxError,
+    },
+    /// A fetch task of a repository panicked or was cancelled.
+    Task {
+        repository: String,
+        source: tokio::task::JoinError,
+    },
+    /// The collected data can't be analyzed.
+    Analysis { message: String },
+    /// A snapshot of fetched data can't be read or written.
+    Snapshot { path: String, source: BoxError },
+    /// A report file can't be written.
+    Report {
+        path: String,
+        source: std::io::Error,
+    },
+}
+
+impl Error {
+    pub fn config(path: impl ToString, source: impl Into<BoxError>) -> Self {
+        Error::Config {
+            path: path.to_string(),
+            source: source.into(),
+        }
+    }
+
+    pub fn git(repository: impl ToString, source: git2::Error) -> Self {
+        Error::Git {
+            repository: repository.to_string(),
+            source,
+        }
+    }
+
+    pub fn forge(repository: impl ToString, source: impl Into<BoxError>) -> Self {
+        Error::Forge {
+            repository: repository.to_string(),
+            source: source.into(),
+        }
+    }
+
+    pub fn task(repository: impl ToString, source: tokio::task::JoinError) -> Self {
+        Error::Task {
+            repository: repository.to_string(),
+            source,
+        }
+    }
+
+    pub fn analysis(message: impl ToString) -> Self {
+        Error::Analysis {
+            message: message.to_string(),
+        }
+    }
+
+    pub fn snapshot(path: impl ToString, source: impl Into<BoxError>) -> Self {
+        Error::Snapshot {
+            path: path.to_string(),
+            source: source.into(),
+        }
+    }
+
+    pub fn report(path: impl ToString, source: std::io::Error) -> Self {
+        Error::Report {
+            path: path.to_string(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config { path, source } => write!(f, "Config `{path}`: {source}"),
+            Error::Git { repository, source } => write!(f, "Git `{repository}`: {source}"),
+            Error::Forge { repository, source } => write!(f, "Forge `{repository}`: {source}"),
+            Error::Task { repository, source } => write!(f, "Task `{repository}`: {source}"),
+            Error::Analysis { message } => write!(f, "Analysis: {message}"),
+            Error::Snapshot { path, source } => write!(f, "Snapshot `{path}`: {source}"),
+            Error::Report { path, source } => write!(f, "Report `{path}`: {source}"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Config { source, .. } => Some(source.as_ref()),
+            Error::Git { source, .. } => Some(source),
+            Error::Forge { source, .. } => Some(source.as_ref()),
+            Error::Task { source, .. } => Some(source),
+            Error::Analysis { .. } => None,
+            Error::Snapshot { source, .. } => Some(source.as_ref()),
+            Error::Report { source, .. } => Some(source),
+        }
+    }
+}
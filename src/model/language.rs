@@ -0,0 +1,100 @@
+use crate::model::{BoxError, Error, Result};
+use indexmap::IndexMap;
+use serde_json::{from_str, Value};
+use std::fs;
+
+/// Extensions as `file_type` of the git stats reports them, used when no config overrides them.
+const BUILTIN: [(&str, &[&str]); 30] = [
+    ("Kotlin", &["kt", "kts"]),
+    ("Java", &["java"]),
+    ("Swift", &["swift"]),
+    ("Objective-C", &["m", "mm"]),
+    ("C", &["c", "h"]),
+    ("C++", &["cc", "cpp", "cxx", "hh", "hpp", "hxx"]),
+    ("C#", &["cs"]),
+    ("Go", &["go"]),
+    ("Rust", &["rs"]),
+    ("Python", &["py"]),
+    ("Ruby", &["rb"]),
+    ("PHP", &["php"]),
+    ("Dart", &["dart"]),
+    ("Scala", &["scala", "sc"]),
+    ("Groovy", &["groovy", "gradle"]),
+    ("JavaScript", &["js", "jsx", "mjs", "cjs"]),
+    ("TypeScript", &["ts", "tsx", "mts", "cts"]),
+    ("Vue", &["vue"]),
+    ("HTML", &["html", "htm"]),
+    ("CSS", &["css", "scss", "sass", "less"]),
+    ("Shell", &["sh", "bash", "zsh"]),
+    ("SQL", &["sql"]),
+    ("Protocol Buffers", &["proto"]),
+    ("JSON", &["json"]),
+    ("YAML", &["yml", "yaml"]),
+    ("TOML", &["toml"]),
+    ("XML", &["xml"]),
+    ("Markdown", &["md"]),
+    ("Dockerfile", &["dockerfile"]),
+    ("Makefile", &["makefile", "mk"]),
+];
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+pub struct Language {
+    pub name: String,
+    /// Lowercase extensions without the dot, or file names of files without one.
+    pub extensions: Vec<String>,
+}
+
+// Create
+impl Language {
+    pub fn from_config(path: &str) -> Result<Vec<Self>> {
+        let json_str = fs::read_to_string(path).map_err(|e| Error::config(path, e))?;
+        Self::parse(&json_str).map_err(|e| Error::config(path, e))
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        BUILTIN
+            .iter()
+            .map(|(name, extensions)| Self::new(name, extensions.to_vec()))
+            .collect()
+    }
+
+    fn new(name: impl ToString, extensions: Vec<impl ToString>) -> Self {
+        Self {
+            name: name.to_string(),
+            extensions: extensions.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+}
+
+// Matching
+impl Language {
+    /// The first language with the file type, so configured languages go before the builtin ones.
+    pub fn find<'a>(languages: &'a [Self], file_type: &str) -> Option<&'a Self> {
+        languages
+            .iter()
+            .find(|language| language.extensions.iter().any(|e| e == file_type))
+    }
+}
+
+// Parser
+impl Language {
+    fn parse(json_str: &str) -> std::result::Result<Vec<Self>, BoxError> {
+        let elements: IndexMap<String, Value> = from_str(json_str)?;
+        let mut result = Vec::new();
+        for (name, details) in elements {
+            let extensions = match details["extensions"].as_array() {
+                Some(extensions) => extensions
+                    .iter()
+                    .map(|extension| match extension.as_str() {
+                        Some(extension) => Ok(extension.trim_start_matches('.').to_lowercase()),
+                        None => Err("Field 'extensions' must contain strings".to_string()),
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?,
+                None => return Err("Not found 'extensions' field".into()),
+            };
+            let new = Self::new(name, extensions);
+            result.push(new);
+        }
+        Ok(result)
+    }
+}
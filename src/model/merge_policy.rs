@@ -0,0 +1,28 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How merge commits are counted.
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum MergePolicy {
+    /// Every commit is walked, a merge counts as its whole diff against the first parent.
+    Full,
+    /// Merge commits are left out, the merged commits are counted on their own.
+    Skip,
+    /// Only the first-parent history is walked, a merge counts as the whole merged change.
+    FirstParent,
+    /// A merge counts only what it changes on top of merging its parents again, such as
+    /// conflict resolutions, like `git show --remerge-diff`.
+    Remerge,
+}
+
+impl MergePolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "full" => Some(MergePolicy::Full),
+            "skip" => Some(MergePolicy::Skip),
+            "first-parent" => Some(MergePolicy::FirstParent),
+            "remerge" => Some(MergePolicy::Remerge),
+            _ => None,
+        }
+    }
+}
@@ -1,9 +1,23 @@
-mod analyzer;
+mod co_author_credit;
+mod commit_type;
+mod conventional_commit;
+mod error;
+mod forge;
+mod language;
+mod merge_policy;
 mod repository;
 mod result;
 mod sprint;
 mod user;
 
+pub use co_author_credit::CoAuthorCredit;
+pub use commit_type::CommitType;
+pub use conventional_commit::ConventionalCommit;
+pub use error::BoxError;
+pub use error::Error;
+pub use forge::ForgeKind;
+pub use language::Language;
+pub use merge_policy::MergePolicy;
 pub use repository::Repository;
 pub use result::Result;
 pub use sprint::Sprint;
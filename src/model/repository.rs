@@ -1,23 +1,38 @@
+use crate::model::{BoxError, Error, ForgeKind, MergePolicy, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use serde_json::{from_str, Value};
-use std::error::Error;
 use std::fs;
-use indexmap::IndexMap;
-
-pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
-#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct Repository {
     pub name: String,
     pub ssh: String,
     pub branch: String,
+    /// Additional branches to count commits from, `*` matches any suffix like `release/*`.
+    #[serde(default)]
+    pub branches: Vec<String>,
     pub owner: String,
+    pub forge: ForgeKind,
+    /// Clone without blobs and fetch them only when a diff needs them.
+    #[serde(default)]
+    pub partial_clone: bool,
+    /// Overrides the global merge commit policy.
+    #[serde(default)]
+    pub merges: Option<MergePolicy>,
+    /// Path globs counted in line stats, added to the global ones.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Path globs left out of line stats, added to the global ones.
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
 
 // New
 impl Repository {
-    pub fn from_config(path: &str) -> crate::model::user::Result<Vec<Self>> {
-        let json_str = fs::read_to_string(path)?;
-        Self::parse(&json_str)
+    pub fn from_config(path: &str) -> Result<Vec<Self>> {
+        let json_str = fs::read_to_string(path).map_err(|e| Error::config(path, e))?;
+        Self::parse(&json_str).map_err(|e| Error::config(path, e))
     }
 
     fn new(
@@ -25,19 +40,26 @@ impl Repository {
         ssh: impl ToString,
         branch: impl ToString,
         owner: impl ToString,
+        forge: ForgeKind,
     ) -> Self {
         Self {
             name: name.to_string(),
             ssh: ssh.to_string(),
             branch: branch.to_string(),
+            branches: vec![],
             owner: owner.to_string(),
+            forge,
+            partial_clone: false,
+            merges: None,
+            include: vec![],
+            exclude: vec![],
         }
     }
 }
 
 // Parser
 impl Repository {
-    fn parse(json_str: &str) -> crate::model::Result<Vec<Self>> {
+    fn parse(json_str: &str) -> std::result::Result<Vec<Self>, BoxError> {
         let elements: IndexMap<String, Value> = from_str(json_str)?;
         let mut result = Vec::new();
         for (name, details) in elements {
@@ -47,12 +69,68 @@ impl Repository {
             let Some(branch) = details["branch"].as_str() else {
                 return Err("Not fond 'branch' field".into());
             };
+            let branches = match &details["branches"] {
+                Value::Null => vec![],
+                Value::Array(branches) => branches
+                    .iter()
+                    .map(|branch| match branch.as_str() {
+                        Some(branch) if branch.matches('*').count() <= 1 => Ok(branch.to_string()),
+                        Some(branch) => {
+                            Err(format!("Branch pattern `{branch}` has more than one '*'"))
+                        }
+                        None => Err("Field 'branches' must contain strings".to_string()),
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?,
+                _ => return Err("Field 'branches' must be an array".into()),
+            };
             let Some(owner) = details["owner"].as_str() else {
                 return Err("Not fond 'owner' field".into());
             };
-            let new = Self::new(name, ssh, branch, owner);
+            let forge = match details["forge"].as_str() {
+                Some(forge) => match ForgeKind::parse(forge) {
+                    Some(forge) => forge,
+                    None => return Err(format!("Unknown forge: {}", forge).into()),
+                },
+                None => ForgeKind::default(),
+            };
+            let partial_clone = match &details["partial_clone"] {
+                Value::Null => false,
+                Value::Bool(partial_clone) => *partial_clone,
+                _ => return Err("Field 'partial_clone' must be a boolean".into()),
+            };
+            let merges = match details["merges"].as_str() {
+                Some(merges) => match MergePolicy::parse(merges) {
+                    Some(merges) => Some(merges),
+                    None => return Err(format!("Unknown merge policy: {}", merges).into()),
+                },
+                None => None,
+            };
+            let include = parse_patterns(&details, "include")?;
+            let exclude = parse_patterns(&details, "exclude")?;
+            let new = Self {
+                branches,
+                partial_clone,
+                merges,
+                include,
+                exclude,
+                ..Self::new(name, ssh, branch, owner, forge)
+            };
             result.push(new);
         }
         Ok(result)
     }
 }
+
+fn parse_patterns(details: &Value, field: &str) -> std::result::Result<Vec<String>, BoxError> {
+    match &details[field] {
+        Value::Null => Ok(vec![]),
+        Value::Array(patterns) => patterns
+            .iter()
+            .map(|pattern| match pattern.as_str() {
+                Some(pattern) => Ok(pattern.to_string()),
+                None => Err(format!("Field '{field}' must contain strings").into()),
+            })
+            .collect(),
+        _ => Err(format!("Field '{field}' must be an array").into()),
+    }
+}
@@ -1,3 +1,3 @@
-use std::error::Error;
+use crate::model::Error;
 
-pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,10 +1,9 @@
-use std::error::Error;
-use std::fs;
-use chrono::{DateTime, FixedOffset};
+use crate::model::{BoxError, Error, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone};
+use chrono_tz::Tz;
 use indexmap::IndexMap;
 use serde_json::{from_str, Value};
-
-pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+use std::fs;
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct Sprint {
@@ -15,9 +14,11 @@ pub struct Sprint {
 
 // Create
 impl Sprint {
-    pub fn from_config(path: &str) -> Result<Vec<Self>> {
-        let json_str = fs::read_to_string(path)?;
-        Self::parse(&json_str)
+    /// Bounds without an offset are local times of the sprint's `timezone`, or of `timezone`
+    /// when the sprint has none.
+    pub fn from_config(path: &str, timezone: Tz) -> Result<Vec<Self>> {
+        let json_str = fs::read_to_string(path).map_err(|e| Error::config(path, e))?;
+        Self::parse(&json_str, timezone).map_err(|e| Error::config(path, e))
     }
 
     fn new(
@@ -27,28 +28,35 @@ impl Sprint {
     ) -> Sprint {
         Self {
             name: name.to_string(),
-            since: since.clone(),
-            until: until.clone()
+            since: *since,
+            until: *until
         }
     }
 }
 
 // Parser
 impl Sprint {
-    fn parse(json_str: &str) -> crate::model::Result<Vec<Self>> {
+    fn parse(json_str: &str, timezone: Tz) -> std::result::Result<Vec<Self>, BoxError> {
         let elements: IndexMap<String, Value> = from_str(json_str)?;
         let mut result = Vec::new();
         for (name, details) in elements {
+            let timezone = match details["timezone"].as_str() {
+                Some(timezone) => match timezone.parse::<Tz>() {
+                    Ok(timezone) => timezone,
+                    Err(_) => return Err(format!("Unknown timezone: {}", timezone).into()),
+                },
+                None => timezone,
+            };
             let Some(since) = details["since"].as_str() else {
                 return Err("Not fond 'since' field".into());
             };
-            let Ok(since) = DateTime::parse_from_rfc3339(since) else {
+            let Some(since) = parse_bound(since, timezone, Bound::Start) else {
                 return Err(format!("Not a valid date time: {}", since).into());
             };
             let Some(until) = details["until"].as_str() else {
                 return Err("Not fond 'until' field".into());
             };
-            let Ok(until) = DateTime::parse_from_rfc3339(until) else {
+            let Some(until) = parse_bound(until, timezone, Bound::End) else {
                 return Err(format!("Not a valid date time: {}", until).into());
             };
             let new = Self::new(name, &since, &until);
@@ -58,3 +66,141 @@ impl Sprint {
     }
 }
 
+#[derive(Clone, Copy)]
+enum Bound {
+    Start,
+    End,
+}
+
+/// RFC 3339 date time, or a local date time or date in `timezone`. A date starts a sprint at
+/// the beginning of the day and ends it at the end of the day.
+fn parse_bound(value: &str, timezone: Tz, bound: Bound) -> Option<DateTime<FixedOffset>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime);
+    }
+    let local = match NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        Ok(datetime) => datetime,
+        Err(_) => {
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+            match bound {
+                Bound::Start => date.and_time(NaiveTime::MIN),
+                Bound::End => date.and_hms_nano_opt(23, 59, 59, 999_999_999)?,
+            }
+        }
+    };
+    // A time repeated by a DST change takes the wider bound, a skipped one the end of the gap.
+    let datetime = timezone.from_local_datetime(&local);
+    let datetime = match bound {
+        Bound::Start => datetime.earliest(),
+        Bound::End => datetime.latest(),
+    };
+    let datetime = match datetime {
+        Some(datetime) => datetime,
+        None => gap_end(&local, timezone)?,
+    };
+    Some(datetime.fixed_offset())
+}
+
+/// First valid instant after the DST gap `local` falls in.
+fn gap_end(local: &NaiveDateTime, timezone: Tz) -> Option<DateTime<Tz>> {
+    const DAY: i64 = 86_400;
+    let offset_at = |timestamp: i64| {
+        let utc = DateTime::from_timestamp(timestamp, 0)?.naive_utc();
+        Some(timezone.offset_from_utc_datetime(&utc).fix().local_minus_utc() as i64)
+    };
+    let seconds = local.and_utc().timestamp();
+    // Read with the offset after the gap the local time is before the change, with the offset
+    // before it after the change.
+    let mut before = seconds - offset_at(seconds + DAY)?;
+    let mut after = seconds - offset_at(seconds - DAY)?;
+    let offset = offset_at(before)?;
+    while after - before > 1 {
+        let middle = (before + after) / 2;
+        if offset_at(middle)? == offset {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+    timezone.timestamp_opt(after, 0).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(value: &str, timezone: Tz, bound: Bound) -> String {
+        parse_bound(value, timezone, bound).unwrap().to_rfc3339()
+    }
+
+    #[test]
+    fn parses_dates_local_times_and_offsets() {
+        assert_eq!(
+            bound("2025-01-06", Tz::UTC, Bound::Start),
+            "2025-01-06T00:00:00+00:00"
+        );
+        assert_eq!(
+            bound("2025-01-19", Tz::UTC, Bound::End),
+            "2025-01-19T23:59:59.999999999+00:00"
+        );
+        assert_eq!(
+            bound("2025-01-06T09:30:00", Tz::Asia__Tokyo, Bound::Start),
+            "2025-01-06T09:30:00+09:00"
+        );
+        assert_eq!(
+            bound("2025-01-06T09:30:00-05:00", Tz::Asia__Tokyo, Bound::Start),
+            "2025-01-06T09:30:00-05:00"
+        );
+        assert!(parse_bound("2025-13-01", Tz::UTC, Bound::Start).is_none());
+    }
+
+    #[test]
+    fn resolves_dst_gap_and_overlap() {
+        let berlin = Tz::Europe__Berlin;
+        // 02:00 to 03:00 is skipped on 2025-03-30.
+        for side in [Bound::Start, Bound::End] {
+            assert_eq!(
+                bound("2025-03-30T02:30:00", berlin, side),
+                "2025-03-30T03:00:00+02:00"
+            );
+        }
+        // 02:00 to 03:00 is repeated on 2025-10-26.
+        assert_eq!(
+            bound("2025-10-26T02:30:00", berlin, Bound::Start),
+            "2025-10-26T02:30:00+02:00"
+        );
+        assert_eq!(
+            bound("2025-10-26T02:30:00", berlin, Bound::End),
+            "2025-10-26T02:30:00+01:00"
+        );
+        // Midnight was skipped in Sao Paulo on 2018-11-04.
+        assert_eq!(
+            bound("2018-11-04", Tz::America__Sao_Paulo, Bound::Start),
+            "2018-11-04T01:00:00-02:00"
+        );
+    }
+
+    #[test]
+    fn sprint_timezone_overrides_default() {
+        let json = r#"{
+            "first": {"since": "2025-01-06", "until": "2025-01-19"},
+            "second": {"since": "2025-01-20", "until": "2025-02-02", "timezone": "Asia/Tokyo"}
+        }"#;
+        let sprints = Sprint::parse(json, Tz::Europe__Berlin).unwrap();
+        let bounds = sprints
+            .iter()
+            .map(|s| (s.since.to_rfc3339(), s.until.offset().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bounds,
+            vec![
+                ("2025-01-06T00:00:00+01:00".to_string(), "+01:00".to_string()),
+                ("2025-01-20T00:00:00+09:00".to_string(), "+09:00".to_string()),
+            ]
+        );
+
+        let unknown = r#"{"first": {"since": "2025-01-06", "until": "2025-01-19", "timezone": "Mars/Base"}}"#;
+        let err = Sprint::parse(unknown, Tz::UTC).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown timezone: Mars/Base");
+    }
+}
@@ -1,9 +1,13 @@
+use crate::forge::ForgeUser;
+use crate::model::{BoxError, Error, Result};
+use indexmap::IndexMap;
+use regex::{Regex, RegexBuilder};
 use serde_json::{from_str, Value};
-use std::error::Error;
 use std::fs;
-use indexmap::IndexMap;
+use std::hash::{Hash, Hasher};
 
-pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+/// Domain of GitHub noreply addresses, their local part is `<id>+<login>` or `<login>`.
+const GITHUB_NOREPLY_DOMAIN: &str = "users.noreply.github.com";
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct User {
@@ -12,13 +16,36 @@ pub struct User {
     pub role: String,
     pub teams: Vec<String>,
     pub emails: Vec<String>,
+    pub logins: Vec<String>,
+    /// Every email of these domains belongs to the user.
+    pub email_domains: Vec<String>,
+    /// Emails matching any of these regular expressions belong to the user.
+    pub email_patterns: Vec<EmailPattern>,
+}
+
+/// Case-insensitive regular expression over the whole email.
+#[derive(Debug, Clone)]
+pub struct EmailPattern(Regex);
+
+impl PartialEq for EmailPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for EmailPattern {}
+
+impl Hash for EmailPattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_str().hash(state)
+    }
 }
 
 // Create
 impl User {
     pub fn from_config(path: &str) -> Result<Vec<Self>> {
-        let json_str = fs::read_to_string(path)?;
-        Self::parse(&json_str)
+        let json_str = fs::read_to_string(path).map_err(|e| Error::config(path, e))?;
+        Self::parse(&json_str).map_err(|e| Error::config(path, e))
     }
 
     fn new(
@@ -27,20 +54,66 @@ impl User {
         role: impl ToString,
         teams: Vec<impl ToString>,
         emails: Vec<impl ToString>,
+        logins: Vec<impl ToString>,
     ) -> Self {
         Self {
             username: username.to_string(),
             avatar_url: avatar_url.to_string(),
             role: role.to_string(),
-            teams: teams.iter().clone().map(|t| t.to_string()).collect(),
-            emails: emails.iter().clone().map(|t| t.to_string()).collect(),
+            teams: teams.iter().map(|t| t.to_string()).collect(),
+            emails: emails.iter().map(|t| t.to_string()).collect(),
+            logins: logins.iter().map(|t| t.to_string()).collect(),
+            email_domains: vec![],
+            email_patterns: vec![],
+        }
+    }
+}
+
+// Matching
+impl User {
+    /// Whether the forge account belongs to the user, by email or by login.
+    pub fn is_forge_user(&self, forge_user: &ForgeUser) -> bool {
+        if let Some(email) = &forge_user.email {
+            if self.is_email_owner(email) {
+                return true;
+            }
+        }
+        self.logins
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(&forge_user.login))
+    }
+
+    /// Whether the email belongs to the user, ignoring case. Besides the listed emails it
+    /// matches the alias rules and GitHub noreply addresses of the user's logins.
+    pub fn is_email_owner(&self, email: &str) -> bool {
+        if self.emails.iter().any(|e| e.eq_ignore_ascii_case(email)) {
+            return true;
+        }
+        let Some((local, domain)) = email.rsplit_once('@') else {
+            return false;
+        };
+        if self
+            .email_domains
+            .iter()
+            .any(|d| d.eq_ignore_ascii_case(domain))
+        {
+            return true;
+        }
+        if domain.eq_ignore_ascii_case(GITHUB_NOREPLY_DOMAIN) {
+            let login = local.split_once('+').map_or(local, |(_, login)| login);
+            if self.logins.iter().any(|l| l.eq_ignore_ascii_case(login)) {
+                return true;
+            }
         }
+        self.email_patterns
+            .iter()
+            .any(|pattern| pattern.0.is_match(email))
     }
 }
 
 // Parser
 impl User {
-    fn parse(json_str: &str) -> crate::model::Result<Vec<Self>> {
+    fn parse(json_str: &str) -> std::result::Result<Vec<Self>, BoxError> {
         let elements: IndexMap<String, Value> = from_str(json_str)?;
         let mut result = Vec::new();
         for (name, details) in elements {
@@ -64,9 +137,62 @@ impl User {
                     .collect(),
                 None => return Err("Not fond 'emails' field".into()),
             };
-            let new = Self::new(name, avatar_url, role, teams, emails);
+            let logins = match details["logins"].as_array() {
+                Some(map) => map
+                    .iter()
+                    .filter_map(|login| login.as_str().map(String::from))
+                    .collect(),
+                None => vec![],
+            };
+            let email_domains = parse_strings(&details, "emailDomains")?
+                .into_iter()
+                .map(|domain| domain.trim_start_matches('@').to_string())
+                .collect();
+            let email_patterns = parse_strings(&details, "emailPatterns")?
+                .into_iter()
+                .map(|pattern| {
+                    RegexBuilder::new(&format!("^(?:{pattern})$"))
+                        .case_insensitive(true)
+                        .build()
+                        .map(EmailPattern)
+                        .map_err(|e| format!("Invalid email pattern `{pattern}`: {e}"))
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let new = Self {
+                email_domains,
+                email_patterns,
+                ..Self::new(name, avatar_url, role, teams, emails, logins)
+            };
             result.push(new);
         }
         Ok(result)
     }
 }
+
+fn parse_strings(details: &Value, field: &str) -> std::result::Result<Vec<String>, BoxError> {
+    match &details[field] {
+        Value::Null => Ok(vec![]),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| match value.as_str() {
+                Some(value) => Ok(value.to_string()),
+                None => Err(format!("Field '{field}' must contain strings").into()),
+            })
+            .collect(),
+        _ => Err(format!("Field '{field}' must be an array").into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::forge::ForgeUser;
+    use crate::test_support::user;
+
+    #[test]
+    fn matches_forge_login_ignoring_case() {
+        let alice = user("alice", "core");
+        assert!(alice.is_forge_user(&ForgeUser::new("Alice", None::<String>)));
+        assert!(alice.is_forge_user(&ForgeUser::new("octo", Some("ALICE@example.com"))));
+        assert!(!alice.is_forge_user(&ForgeUser::new("alicia", None::<String>)));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::analyze::{SprintsAnalyzed, UserDataAnalyzed};
+use crate::model::{Error, Result};
+use indexmap::IndexSet;
+use std::collections::HashMap;
+use std::fs;
+
+/// Column and metric names are fixed English identifiers like the JSON keys, `--lang` only
+/// applies to the reports for people, so scripts don't break on a change of the language.
+pub trait CsvReport {
+    /// One row per sprint and user with a column per metric. The language breakdown adds
+    /// `insertions.<language>` and `deletions.<language>` columns, the commit types
+    /// `commits.<type>` and `change_lines.<type>`, for whatever any user of the team has.
+    fn report_create(&self, team: &str) -> Result<()>;
+    /// One row per sprint, user and metric, handy for pivot tables, with the same breakdown
+    /// metrics as the wide report.
+    fn long_report_create(&self, team: &str) -> Result<()>;
+}
+
+impl CsvReport for SprintsAnalyzed {
+    fn report_create(&self, team: &str) -> Result<()> {
+        let team_data = self
+            .iter()
+            .flat_map(|(sprint, data)| {
+                data.iter()
+                    .filter(|(u, _)| u.teams.iter().any(|t| t == team))
+                    .map(move |(user, data)| (sprint, user, data))
+            })
+            .collect::<Vec<_>>();
+        let breakdown_columns = team_data
+            .iter()
+            .flat_map(|(_, _, data)| breakdown(data))
+            .map(|(metric, _)| metric)
+            .collect::<IndexSet<_>>();
+        let header = ["sprint", "team", "user"]
+            .into_iter()
+            .chain(metrics(&UserDataAnalyzed::default()).iter().map(|(name, _)| *name))
+            .chain(breakdown_columns.iter().map(String::as_str))
+            .map(csv_field)
+            .collect::<Vec<_>>();
+        let mut rows = vec![header.join(",")];
+        for (sprint, user, data) in team_data {
+            let breakdown = breakdown(data).into_iter().collect::<HashMap<_, _>>();
+            let row = [&sprint.name, team, &user.username]
+                .into_iter()
+                .map(csv_field)
+                .chain(metrics(data).iter().map(|(_, value)| value.to_string()))
+                .chain(
+                    breakdown_columns
+                        .iter()
+                        .map(|metric| breakdown.get(metric).copied().unwrap_or(0).to_string()),
+                )
+                .collect::<Vec<_>>();
+            rows.push(row.join(","));
+        }
+        write(&format!("{team}.csv"), rows)
+    }
+
+    fn long_report_create(&self, team: &str) -> Result<()> {
+        let mut rows = vec!["sprint,user,metric,value".to_string()];
+        for (sprint, data) in self {
+            for (user, data) in data.iter().filter(|(u, _)| u.teams.iter().any(|t| t == team)) {
+                let metrics = metrics(data)
+                    .into_iter()
+                    .map(|(metric, value)| (metric.to_string(), value))
+                    .chain(breakdown(data));
+                for (metric, value) in metrics {
+                    let row = [sprint.name.as_str(), &user.username, &metric]
+                        .into_iter()
+                        .map(csv_field)
+                        .chain([value.to_string()])
+                        .collect::<Vec<_>>();
+                    rows.push(row.join(","));
+                }
+            }
+        }
+        write(&format!("{team}.long.csv"), rows)
+    }
+}
+
+fn metrics(data: &UserDataAnalyzed) -> Vec<(&'static str, usize)> {
+    let commits = &data.commits;
+    let pull_requests = &data.pull_requests;
+    vec![
+        ("files_changed", commits.files_changed),
+        ("change_lines", commits.change_lines),
+        ("insertions", commits.insertions),
+        ("deletions", commits.deletions),
+        ("commits", commits.commits),
+        ("co_authored_commits", commits.co_authored_commits),
+        ("new_work", commits.new_work),
+        ("rework_own", commits.rework_own),
+        ("rework_others", commits.rework_others),
+        ("conventional_commits", commits.conventional_commits),
+        ("breaking_changes", commits.breaking_changes),
+        ("create_pull_requests", pull_requests.create_pull_requests),
+        ("merged_pull_requests", pull_requests.merged_pull_requests),
+        ("closed_pull_requests", pull_requests.closed_pull_requests),
+        ("received_discussions", pull_requests.received_discussions),
+        ("approver_assigned", pull_requests.approver_assigned),
+        ("approver_conducted", pull_requests.approver_conducted),
+        (
+            "approver_added_discussions",
+            pull_requests.approver_added_discussions,
+        ),
+    ]
+}
+
+/// Lines per language and commits per commit type of the user.
+fn breakdown(data: &UserDataAnalyzed) -> Vec<(String, usize)> {
+    let languages = data.commits.languages.iter().flat_map(|(name, lines)| {
+        [
+            (format!("insertions.{name}"), lines.insertions),
+            (format!("deletions.{name}"), lines.deletions),
+        ]
+    });
+    let commit_types = data.commits.commit_types.iter().flat_map(|(name, stats)| {
+        [
+            (format!("commits.{name}"), stats.commits),
+            (format!("change_lines.{name}"), stats.change_lines),
+        ]
+    });
+    languages.chain(commit_types).collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write(path: &str, rows: Vec<String>) -> Result<()> {
+    let mut csv = rows.join("\r\n");
+    csv.push_str("\r\n");
+    fs::write(path, csv).map_err(|e| Error::report(path, e))
+}
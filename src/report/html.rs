@@ -0,0 +1,490 @@
+use crate::analyze::{
+    CommitTypeStats, RepoFailures, RepoHotspots, SprintsAnalyzed, SprintsHotspots,
+    SprintsKnowledge, TeamKnowledge, UnmatchedAuthors, UserDataAnalyzed,
+};
+use crate::model::{Error, Result, User};
+use crate::report::i18n::{Label, Lang};
+use crate::report::svg::{self, escape, Series};
+use itertools::Itertools;
+use std::fs;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:24px;color:#222}\
+table{border-collapse:collapse;margin-bottom:24px}\
+th,td{border:1px solid #ddd;padding:4px 8px;text-align:right}\
+th:first-child,td:first-child{text-align:left}\
+figure{display:inline-block;margin:0 16px 16px 0}\
+figcaption{font-weight:bold;margin-bottom:4px}\
+caption{font-weight:bold;text-align:left;margin-bottom:4px}";
+
+pub trait HtmlReport {
+    fn report_create(
+        &self,
+        team: &str,
+        lang: Lang,
+        failures: &RepoFailures,
+        unmatched: &UnmatchedAuthors,
+        hotspots: &SprintsHotspots,
+        knowledge: &SprintsKnowledge,
+    ) -> Result<()>;
+}
+
+impl HtmlReport for SprintsAnalyzed {
+    fn report_create(
+        &self,
+        team: &str,
+        lang: Lang,
+        failures: &RepoFailures,
+        unmatched: &UnmatchedAuthors,
+        hotspots: &SprintsHotspots,
+        knowledge: &SprintsKnowledge,
+    ) -> Result<()> {
+        let sprints = self
+            .iter()
+            .map(|(sprint, data)| {
+                let data = data
+                    .iter()
+                    .filter(|(u, _)| u.teams.iter().any(|t| t == team))
+                    .collect::<Vec<_>>();
+                (sprint, data)
+            })
+            .collect::<Vec<_>>();
+        let labels = sprints
+            .iter()
+            .map(|(sprint, _)| sprint.name.clone())
+            .collect::<Vec<_>>();
+        let users = sprints
+            .first()
+            .map(|(_, data)| data.iter().map(|(u, _)| u).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let mut html = format!(
+            "<!DOCTYPE html><html lang=\"{}\"><head><meta charset=\"utf-8\"><title>{}</title><style>{STYLE}</style></head><body>",
+            lang.code(),
+            escape(team)
+        );
+
+        html.push_str(&heading(Label::SprintTrends, lang));
+        for (title, metric) in user_metrics() {
+            let series = users
+                .iter()
+                .map(|user| {
+                    let values = sprints
+                        .iter()
+                        .map(|(_, data)| user_data(data, user).map_or(0, metric))
+                        .collect();
+                    (user.username.clone(), values)
+                })
+                .collect::<Vec<Series>>();
+            html.push_str(&figure(
+                title.text(lang),
+                &svg::line_chart(&labels, &series),
+            ));
+        }
+
+        html.push_str(&heading(Label::TeamTotals, lang));
+        for (title, group) in team_metrics() {
+            let series = group
+                .iter()
+                .map(|(title, metric)| {
+                    let values = sprints
+                        .iter()
+                        .map(|(_, data)| data.iter().map(|(_, d)| metric(d)).sum())
+                        .collect();
+                    (title.text(lang).to_string(), values)
+                })
+                .collect::<Vec<Series>>();
+            html.push_str(&figure(title.text(lang), &svg::bar_chart(&labels, &series)));
+        }
+
+        html.push_str(&heading(Label::Review, lang));
+        let usernames = users.iter().map(|u| u.username.clone()).collect::<Vec<_>>();
+        let series = review_metrics()
+            .into_iter()
+            .map(|(title, metric)| {
+                let values = users
+                    .iter()
+                    .map(|user| {
+                        sprints
+                            .iter()
+                            .filter_map(|(_, data)| user_data(data, user))
+                            .map(metric)
+                            .sum()
+                    })
+                    .collect();
+                (title.text(lang).to_string(), values)
+            })
+            .collect::<Vec<Series>>();
+        html.push_str(&figure(
+            Label::ReviewerActivity.text(lang),
+            &svg::bar_chart(&usernames, &series),
+        ));
+
+        html.push_str(&heading(Label::Sprints, lang));
+        for (sprint, data) in &sprints {
+            html.push_str(&format!(
+                "<h2>{} ({} - {})</h2>",
+                escape(&sprint.name),
+                sprint.since.format("%d.%m.%Y"),
+                sprint.until.format("%d.%m.%Y"),
+            ));
+            html.push_str(&table(data, lang));
+            html.push_str(&team_commit_types_table(data, lang));
+        }
+
+        if hotspots.iter().any(|(_, repos)| !repos.is_empty()) {
+            html.push_str(&heading(Label::Hotspots, lang));
+            html.push_str(&format!("<p>{}</p>", escape(Label::HotspotsNote.text(lang))));
+            for (sprint, repos) in hotspots.iter().filter(|(_, repos)| !repos.is_empty()) {
+                html.push_str(&format!(
+                    "<h2>{} ({} - {})</h2>",
+                    escape(&sprint.name),
+                    sprint.since.format("%d.%m.%Y"),
+                    sprint.until.format("%d.%m.%Y"),
+                ));
+                for repo in repos {
+                    html.push_str(&hotspots_tables(repo, lang));
+                }
+            }
+        }
+
+        if knowledge.iter().any(|(_, knowledge)| has_knowledge(knowledge)) {
+            html.push_str(&heading(Label::Knowledge, lang));
+            html.push_str(&format!("<p>{}</p>", escape(Label::KnowledgeNote.text(lang))));
+            for (sprint, knowledge) in knowledge.iter().filter(|(_, k)| has_knowledge(k)) {
+                html.push_str(&format!(
+                    "<h2>{} ({} - {})</h2>",
+                    escape(&sprint.name),
+                    sprint.since.format("%d.%m.%Y"),
+                    sprint.until.format("%d.%m.%Y"),
+                ));
+                html.push_str(&knowledge_tables(knowledge, lang));
+            }
+        }
+
+        if !failures.is_empty() {
+            html.push_str(&heading(Label::FailedRepositories, lang));
+            html.push_str("<ul>");
+            for (repo, err) in failures {
+                html.push_str(&format!(
+                    "<li><b>{}</b>: {}</li>",
+                    escape(&repo.name),
+                    escape(err)
+                ));
+            }
+            html.push_str("</ul>");
+        }
+        if !unmatched.is_empty() {
+            html.push_str(&heading(Label::UnmatchedAuthors, lang));
+            html.push_str(&format!(
+                "<p>{}</p><table><tr><th>{}</th><th>{}</th><th>{}</th></tr>",
+                escape(Label::UnmatchedAuthorsNote.text(lang)),
+                escape(Label::Email.text(lang)),
+                escape(Label::TotalCommits.text(lang)),
+                escape(Label::Lines.text(lang)),
+            ));
+            for author in unmatched {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape(&author.email),
+                    author.commits,
+                    author.change_lines
+                ));
+            }
+            html.push_str("</table>");
+        }
+        html.push_str("</body></html>");
+
+        let path = format!("{team}.html");
+        fs::write(&path, html).map_err(|e| Error::report(&path, e))
+    }
+}
+
+type Metric = fn(&UserDataAnalyzed) -> usize;
+
+fn user_metrics() -> Vec<(Label, Metric)> {
+    vec![
+        (Label::Contribution, |d| d.commits.change_lines),
+        (Label::CommitsMade, |d| d.commits.commits),
+        (Label::PullRequestsCreated, |d| {
+            d.pull_requests.create_pull_requests
+        }),
+        (Label::ReviewsConducted, |d| {
+            d.pull_requests.approver_conducted
+        }),
+    ]
+}
+
+fn team_metrics() -> Vec<(Label, Vec<(Label, Metric)>)> {
+    vec![
+        (
+            Label::CodeLines,
+            vec![
+                (Label::TotalLinesAdded, |d| d.commits.insertions),
+                (Label::TotalLinesDeleted, |d| d.commits.deletions),
+            ],
+        ),
+        (
+            Label::Activity,
+            vec![
+                (Label::TotalCommits, |d| d.commits.commits),
+                (Label::TotalPullRequestsCreated, |d| {
+                    d.pull_requests.create_pull_requests
+                }),
+                (Label::TotalPullRequestsMerged, |d| {
+                    d.pull_requests.merged_pull_requests
+                }),
+                (Label::TotalReviewsConducted, |d| {
+                    d.pull_requests.approver_conducted
+                }),
+            ],
+        ),
+    ]
+}
+
+fn review_metrics() -> Vec<(Label, Metric)> {
+    vec![
+        (Label::ReviewerAssigned, |d| {
+            d.pull_requests.approver_assigned
+        }),
+        (Label::ReviewsConducted, |d| {
+            d.pull_requests.approver_conducted
+        }),
+        (Label::DiscussionsStarted, |d| {
+            d.pull_requests.approver_added_discussions
+        }),
+    ]
+}
+
+fn user_data<'a>(
+    data: &[&'a (User, UserDataAnalyzed)],
+    user: &User,
+) -> Option<&'a UserDataAnalyzed> {
+    data.iter()
+        .find(|(u, _)| u.username == user.username)
+        .map(|(_, d)| d)
+}
+
+fn heading(label: Label, lang: Lang) -> String {
+    format!("<h1>{}</h1>", escape(label.text(lang)))
+}
+
+fn figure(title: &str, svg: &str) -> String {
+    format!(
+        "<figure><figcaption>{}</figcaption>{svg}</figure>",
+        escape(title)
+    )
+}
+
+fn hotspots_tables(repo: &RepoHotspots, lang: Lang) -> String {
+    let mut html = format!("<h3>{}</h3>", escape(&repo.repository));
+    for (label, paths) in [
+        (Label::HotFiles, &repo.files),
+        (Label::HotDirectories, &repo.directories),
+    ] {
+        if paths.is_empty() {
+            continue;
+        }
+        html.push_str(&format!(
+            "<table><caption>{}</caption><tr><th>{}</th><th>{}</th></tr>",
+            escape(label.text(lang)),
+            escape(Label::Path.text(lang)),
+            escape(Label::TotalCommits.text(lang)),
+        ));
+        for path in paths {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape(&path.path),
+                path.commits
+            ));
+        }
+        html.push_str("</table>");
+    }
+    if !repo.coupling.is_empty() {
+        html.push_str(&format!(
+            "<table><caption>{}</caption><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+            escape(Label::ChangeCoupling.text(lang)),
+            escape(Label::Path.text(lang)),
+            escape(Label::Path.text(lang)),
+            escape(Label::TotalCommits.text(lang)),
+            escape(Label::CouplingDegree.text(lang)),
+        ));
+        for coupled in &repo.coupling {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&coupled.first),
+                escape(&coupled.second),
+                coupled.commits,
+                coupled.degree
+            ));
+        }
+        html.push_str("</table>");
+    }
+    html
+}
+
+fn has_knowledge(knowledge: &TeamKnowledge) -> bool {
+    !knowledge.bus_factor.is_empty()
+        || knowledge.breadth.iter().any(|user| !user.repositories.is_empty())
+}
+
+fn knowledge_tables(knowledge: &TeamKnowledge, lang: Lang) -> String {
+    let mut html = String::new();
+    if !knowledge.bus_factor.is_empty() {
+        html.push_str(&format!(
+            "<table><caption>{}</caption><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+            escape(Label::BusFactor.text(lang)),
+            escape(Label::Repository.text(lang)),
+            escape(Label::Module.text(lang)),
+            escape(Label::Owner.text(lang)),
+            escape(Label::OwnerShare.text(lang)),
+            escape(Label::Lines.text(lang)),
+        ));
+        for module in &knowledge.bus_factor {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&module.repository),
+                escape(&module.module),
+                escape(&module.owner),
+                module.share,
+                module.lines
+            ));
+        }
+        html.push_str("</table>");
+    }
+    html.push_str(&format!(
+        "<table><caption>{}</caption><tr><th>{}</th><th>{}</th><th>{}</th></tr>",
+        escape(Label::Breadth.text(lang)),
+        escape(Label::User.text(lang)),
+        escape(Label::Repositories.text(lang)),
+        escape(Label::Modules.text(lang)),
+    ));
+    for user in &knowledge.breadth {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&user.username),
+            escape(&user.repositories.join(", ")),
+            user.modules.len()
+        ));
+    }
+    html.push_str("</table>");
+    html
+}
+
+fn table(data: &[&(User, UserDataAnalyzed)], lang: Lang) -> String {
+    let columns: Vec<(&str, Metric)> = vec![
+        (Label::Lines.text(lang), |d| d.commits.change_lines),
+        ("+", |d| d.commits.insertions),
+        ("-", |d| d.commits.deletions),
+        (Label::NewWork.text(lang), |d| d.commits.new_work),
+        (Label::ReworkOwn.text(lang), |d| d.commits.rework_own),
+        (Label::ReworkOthers.text(lang), |d| d.commits.rework_others),
+        (Label::TotalCommits.text(lang), |d| d.commits.commits),
+        (Label::CoAuthoredCommits.text(lang), |d| {
+            d.commits.co_authored_commits
+        }),
+        (Label::ConventionalCommits.text(lang), |d| {
+            d.commits.conventional_commits
+        }),
+        (Label::BreakingChanges.text(lang), |d| d.commits.breaking_changes),
+        (Label::PullRequestsCreated.text(lang), |d| {
+            d.pull_requests.create_pull_requests
+        }),
+        (Label::PullRequestsMerged.text(lang), |d| {
+            d.pull_requests.merged_pull_requests
+        }),
+        (Label::PullRequestsClosed.text(lang), |d| {
+            d.pull_requests.closed_pull_requests
+        }),
+        (Label::DiscussionsReceived.text(lang), |d| {
+            d.pull_requests.received_discussions
+        }),
+        (Label::ReviewerAssigned.text(lang), |d| {
+            d.pull_requests.approver_assigned
+        }),
+        (Label::ReviewsConducted.text(lang), |d| {
+            d.pull_requests.approver_conducted
+        }),
+        (Label::DiscussionsStarted.text(lang), |d| {
+            d.pull_requests.approver_added_discussions
+        }),
+    ];
+    let mut html = String::from("<table><tr><th></th>");
+    for (title, _) in &columns {
+        html.push_str(&format!("<th>{}</th>", escape(title)));
+    }
+    html.push_str(&format!(
+        "<th>{}</th><th>{}</th></tr>",
+        escape(Label::Languages.text(lang)),
+        escape(Label::CommitTypes.text(lang))
+    ));
+    for (user, data) in data {
+        html.push_str(&format!(
+            "<tr><td><b>{}</b> <i>{}</i></td>",
+            escape(&user.username),
+            escape(&user.role)
+        ));
+        for (_, metric) in &columns {
+            html.push_str(&format!("<td>{}</td>", metric(data)));
+        }
+        let languages = data
+            .commits
+            .languages
+            .iter()
+            .map(|(name, lines)| {
+                format!(
+                    "{} +{} / -{}",
+                    escape(Label::language(name, lang)),
+                    lines.insertions,
+                    lines.deletions
+                )
+            })
+            .join("<br>");
+        let commit_types = data
+            .commits
+            .commit_types
+            .iter()
+            .map(|(name, stats)| {
+                format!(
+                    "{} {} ({})",
+                    escape(name),
+                    stats.commits,
+                    stats.change_lines
+                )
+            })
+            .join("<br>");
+        html.push_str(&format!("<td>{languages}</td><td>{commit_types}</td></tr>"));
+    }
+    html.push_str("</table>");
+    html
+}
+
+fn team_commit_types_table(data: &[&(User, UserDataAnalyzed)], lang: Lang) -> String {
+    let commit_types = CommitTypeStats::team(data.iter().map(|(_, data)| &data.commits));
+    if commit_types.is_empty() {
+        return String::new();
+    }
+    let conventional = data.iter().map(|(_, d)| d.commits.conventional_commits).sum::<usize>();
+    let commits = data.iter().map(|(_, d)| d.commits.commits).sum::<usize>();
+    let mut html = format!(
+        "<table><caption>{} ({}: {} / {})</caption><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+        escape(Label::TeamCommitTypes.text(lang)),
+        escape(Label::ConventionalCommits.text(lang)),
+        conventional,
+        commits,
+        escape(Label::CommitType.text(lang)),
+        escape(Label::TotalCommits.text(lang)),
+        escape(Label::Lines.text(lang)),
+        escape(Label::Scopes.text(lang)),
+    );
+    for (name, stats) in &commit_types {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(name),
+            stats.commits,
+            stats.change_lines,
+            escape(&stats.scopes.iter().join(", "))
+        ));
+    }
+    html.push_str("</table>");
+    html
+}
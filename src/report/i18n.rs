@@ -0,0 +1,241 @@
+use crate::analyze::OTHER_LANGUAGE;
+use clap::ValueEnum;
+
+/// Language of the human-readable reports.
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Lang {
+    En,
+    Ru,
+}
+
+impl Lang {
+    /// BCP 47 language tag.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Ru => "ru",
+        }
+    }
+}
+
+/// User-facing string shared by the report renderers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Label {
+    Sprints,
+    SprintTrends,
+    TeamTotals,
+    Review,
+    ReviewerActivity,
+    CodeLines,
+    Activity,
+    Contribution,
+    Languages,
+    OtherLanguage,
+    Lines,
+    CommitsMade,
+    CoAuthoredCommits,
+    NewWork,
+    ReworkOwn,
+    ReworkOthers,
+    PullRequestsCreated,
+    PullRequestsMerged,
+    PullRequestsClosed,
+    DiscussionsReceived,
+    ReviewerAssigned,
+    ReviewsConducted,
+    DiscussionsStarted,
+    TotalLinesAdded,
+    TotalLinesDeleted,
+    TotalCommits,
+    TotalPullRequestsCreated,
+    TotalPullRequestsMerged,
+    TotalReviewsConducted,
+    FailedRepositories,
+    FailedRepositoriesNote,
+    UnmatchedAuthors,
+    UnmatchedAuthorsNote,
+    Email,
+    Hotspots,
+    HotspotsNote,
+    HotFiles,
+    HotDirectories,
+    ChangeCoupling,
+    Path,
+    CouplingDegree,
+    Knowledge,
+    KnowledgeNote,
+    BusFactor,
+    Breadth,
+    Repository,
+    Module,
+    Owner,
+    OwnerShare,
+    User,
+    Repositories,
+    Modules,
+    CommitTypes,
+    TeamCommitTypes,
+    CommitType,
+    Scopes,
+    ConventionalCommits,
+    BreakingChanges,
+}
+
+// Catalog
+impl Label {
+    pub fn text(self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::En => self.en(),
+            Lang::Ru => self.ru(),
+        }
+    }
+
+    /// Language names are shown as configured, except the catch-all one.
+    pub fn language(name: &str, lang: Lang) -> &str {
+        if name == OTHER_LANGUAGE {
+            Label::OtherLanguage.text(lang)
+        } else {
+            name
+        }
+    }
+
+    fn en(self) -> &'static str {
+        match self {
+            Label::Sprints => "Sprints",
+            Label::SprintTrends => "Sprint trends",
+            Label::TeamTotals => "Team totals",
+            Label::Review => "Review",
+            Label::ReviewerActivity => "Reviewer activity",
+            Label::CodeLines => "Lines of code",
+            Label::Activity => "Activity",
+            Label::Contribution => "Contribution to the codebase",
+            Label::Languages => "Languages",
+            Label::OtherLanguage => "Other",
+            Label::Lines => "Lines",
+            Label::CommitsMade => "Commits made",
+            Label::CoAuthoredCommits => "Co-authored commits",
+            Label::NewWork => "New work, inserted lines",
+            Label::ReworkOwn => "Rework of own code, replaced lines",
+            Label::ReworkOthers => "Rework of others' code, replaced lines",
+            Label::PullRequestsCreated => "PRs created",
+            Label::PullRequestsMerged => "PRs merged",
+            Label::PullRequestsClosed => "PRs closed",
+            Label::DiscussionsReceived => "Discussions received",
+            Label::ReviewerAssigned => "Assigned as reviewer",
+            Label::ReviewsConducted => "Reviews conducted",
+            Label::DiscussionsStarted => "Discussions started",
+            Label::TotalLinesAdded => "Lines added",
+            Label::TotalLinesDeleted => "Lines deleted",
+            Label::TotalCommits => "Commits",
+            Label::TotalPullRequestsCreated => "PRs created",
+            Label::TotalPullRequestsMerged => "PRs merged",
+            Label::TotalReviewsConducted => "Reviews conducted",
+            Label::FailedRepositories => "Failed repositories",
+            Label::FailedRepositoriesNote => {
+                "Data of these repositories is not included in the metrics."
+            }
+            Label::UnmatchedAuthors => "Unmatched commit authors",
+            Label::UnmatchedAuthorsNote => {
+                "Commits of these emails in the sprints belong to no user, add the emails or alias rules to the users config."
+            }
+            Label::Email => "Email",
+            Label::Hotspots => "Hotspots",
+            Label::HotspotsNote => {
+                "Most changed files and directories and files changed together, by commits of the team."
+            }
+            Label::HotFiles => "Most changed files",
+            Label::HotDirectories => "Most changed directories",
+            Label::ChangeCoupling => "Change coupling",
+            Label::Path => "Path",
+            Label::CouplingDegree => "Coupling, %",
+            Label::Knowledge => "Knowledge distribution",
+            Label::KnowledgeNote => {
+                "Modules where one team member owns most of the lines at the sprint end, and repositories and top-level modules each member committed to."
+            }
+            Label::BusFactor => "Bus factor 1 modules",
+            Label::Breadth => "Breadth",
+            Label::Repository => "Repository",
+            Label::Module => "Module",
+            Label::Owner => "Owner",
+            Label::OwnerShare => "Share, %",
+            Label::User => "User",
+            Label::Repositories => "Repositories",
+            Label::Modules => "Modules",
+            Label::CommitTypes => "Commit types",
+            Label::TeamCommitTypes => "Commit types of the team",
+            Label::CommitType => "Type",
+            Label::Scopes => "Scopes",
+            Label::ConventionalCommits => "Conventional commits",
+            Label::BreakingChanges => "Breaking changes",
+        }
+    }
+
+    fn ru(self) -> &'static str {
+        match self {
+            Label::Sprints => "Спринты",
+            Label::SprintTrends => "Динамика по спринтам",
+            Label::TeamTotals => "Итоги команды",
+            Label::Review => "Ревью",
+            Label::ReviewerActivity => "Активность ревьюеров",
+            Label::CodeLines => "Строки кода",
+            Label::Activity => "Активность",
+            Label::Contribution => "Вклад в кодовую базу",
+            Label::Languages => "Языки",
+            Label::OtherLanguage => "Другое",
+            Label::Lines => "Строк",
+            Label::CommitsMade => "Сделал коммитов",
+            Label::CoAuthoredCommits => "Совместных коммитов",
+            Label::NewWork => "Новый код, добавлено строк",
+            Label::ReworkOwn => "Переписал своего кода, заменено строк",
+            Label::ReworkOthers => "Переписал чужого кода, заменено строк",
+            Label::PullRequestsCreated => "Создал PR",
+            Label::PullRequestsMerged => "Слил PR",
+            Label::PullRequestsClosed => "Закрыл PR",
+            Label::DiscussionsReceived => "Получил дискуссий",
+            Label::ReviewerAssigned => "Был назначен ревьюером",
+            Label::ReviewsConducted => "Провел ревью",
+            Label::DiscussionsStarted => "Завел дискуссий",
+            Label::TotalLinesAdded => "Добавлено строк",
+            Label::TotalLinesDeleted => "Удалено строк",
+            Label::TotalCommits => "Коммитов",
+            Label::TotalPullRequestsCreated => "Создано PR",
+            Label::TotalPullRequestsMerged => "Слито PR",
+            Label::TotalReviewsConducted => "Проведено ревью",
+            Label::FailedRepositories => "Не загружены репозитории",
+            Label::FailedRepositoriesNote => "Данные этих репозиториев не учтены в метриках.",
+            Label::UnmatchedAuthors => "Неопознанные авторы коммитов",
+            Label::UnmatchedAuthorsNote => {
+                "Коммиты этих адресов в спринтах не относятся ни к одному пользователю, добавьте адреса или правила псевдонимов в конфигурацию пользователей."
+            }
+            Label::Email => "Почта",
+            Label::Hotspots => "Горячие точки",
+            Label::HotspotsNote => {
+                "Чаще всего изменяемые файлы и каталоги и файлы, меняющиеся вместе, по коммитам команды."
+            }
+            Label::HotFiles => "Чаще всего изменяемые файлы",
+            Label::HotDirectories => "Чаще всего изменяемые каталоги",
+            Label::ChangeCoupling => "Связанные изменения",
+            Label::Path => "Путь",
+            Label::CouplingDegree => "Связность, %",
+            Label::Knowledge => "Распределение знаний",
+            Label::KnowledgeNote => {
+                "Модули, большая часть строк которых на конец спринта принадлежит одному участнику команды, и репозитории и модули верхнего уровня, в которые коммитил каждый участник."
+            }
+            Label::BusFactor => "Модули с bus factor 1",
+            Label::Breadth => "Широта",
+            Label::Repository => "Репозиторий",
+            Label::Module => "Модуль",
+            Label::Owner => "Владелец",
+            Label::OwnerShare => "Доля, %",
+            Label::User => "Пользователь",
+            Label::Repositories => "Репозитории",
+            Label::Modules => "Модули",
+            Label::CommitTypes => "Типы коммитов",
+            Label::TeamCommitTypes => "Типы коммитов команды",
+            Label::CommitType => "Тип",
+            Label::Scopes => "Области",
+            Label::ConventionalCommits => "Коммитов по Conventional Commits",
+            Label::BreakingChanges => "Несовместимых изменений",
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::analyze::{
+    CommitAnalyzed, CommitTypeStats, PullRequestAnalyzed, RepoFailures, RepoHotspots,
+    SprintsAnalyzed, SprintsHotspots, SprintsKnowledge, TeamKnowledge, UnmatchedAuthors,
+};
+use crate::model::{Error, Result};
+use chrono::{DateTime, FixedOffset, Utc};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fs;
+
+/// Version of `schema/report.schema.json`, bumped with the schema `const` in the same commit as
+/// any change of the output shape, added fields included since the schema allows no unknown ones.
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct JsonDocument<'a> {
+    schema_version: u32,
+    generator: JsonGenerator,
+    team: &'a str,
+    sprints: Vec<JsonSprint<'a>>,
+    failed_repositories: Vec<JsonFailure<'a>>,
+    unmatched_authors: &'a UnmatchedAuthors,
+    hotspots: Vec<JsonSprintHotspots<'a>>,
+    knowledge: Vec<JsonSprintKnowledge<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonGenerator {
+    name: &'static str,
+    version: &'static str,
+    generated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct JsonSprint<'a> {
+    name: &'a str,
+    since: DateTime<FixedOffset>,
+    until: DateTime<FixedOffset>,
+    users: Vec<JsonUser<'a>>,
+    /// Commit types of the users summed up.
+    commit_types: IndexMap<String, CommitTypeStats>,
+}
+
+#[derive(Serialize)]
+struct JsonUser<'a> {
+    username: &'a str,
+    avatar_url: &'a str,
+    role: &'a str,
+    teams: &'a [String],
+    commits: &'a CommitAnalyzed,
+    pull_requests: &'a PullRequestAnalyzed,
+}
+
+#[derive(Serialize)]
+struct JsonSprintHotspots<'a> {
+    sprint: &'a str,
+    repositories: &'a [RepoHotspots],
+}
+
+#[derive(Serialize)]
+struct JsonSprintKnowledge<'a> {
+    sprint: &'a str,
+    #[serde(flatten)]
+    knowledge: &'a TeamKnowledge,
+}
+
+#[derive(Serialize)]
+struct JsonFailure<'a> {
+    repository: &'a str,
+    error: &'a str,
+}
+
+pub trait JsonReport {
+    fn report_create(
+        &self,
+        team: &str,
+        failures: &RepoFailures,
+        unmatched: &UnmatchedAuthors,
+        hotspots: &SprintsHotspots,
+        knowledge: &SprintsKnowledge,
+    ) -> Result<()>;
+}
+
+impl JsonReport for SprintsAnalyzed {
+    fn report_create(
+        &self,
+        team: &str,
+        failures: &RepoFailures,
+        unmatched: &UnmatchedAuthors,
+        hotspots: &SprintsHotspots,
+        knowledge: &SprintsKnowledge,
+    ) -> Result<()> {
+        let path = format!("{team}.json");
+        let json_str = render(self, team, failures, unmatched, hotspots, knowledge)
+            .map_err(|e| Error::report(&path, std::io::Error::other(e)))?;
+        fs::write(&path, json_str).map_err(|e| Error::report(&path, e))
+    }
+}
+
+fn render(
+    sprints: &SprintsAnalyzed,
+    team: &str,
+    failures: &RepoFailures,
+    unmatched: &UnmatchedAuthors,
+    hotspots: &SprintsHotspots,
+    knowledge: &SprintsKnowledge,
+) -> serde_json::Result<String> {
+    let sprints = sprints
+        .iter()
+        .map(|(sprint, data)| {
+            let data = data
+                .iter()
+                .filter(|(u, _)| u.teams.iter().any(|t| t == team))
+                .collect::<Vec<_>>();
+            JsonSprint {
+                name: &sprint.name,
+                since: sprint.since,
+                until: sprint.until,
+                users: data
+                    .iter()
+                    .map(|(user, data)| JsonUser {
+                        username: &user.username,
+                        avatar_url: &user.avatar_url,
+                        role: &user.role,
+                        teams: &user.teams,
+                        commits: &data.commits,
+                        pull_requests: &data.pull_requests,
+                    })
+                    .collect(),
+                commit_types: CommitTypeStats::team(data.iter().map(|(_, d)| &d.commits)),
+            }
+        })
+        .collect();
+    let doc = JsonDocument {
+        schema_version: SCHEMA_VERSION,
+        generator: JsonGenerator {
+            name: env!("CARGO_BIN_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            generated_at: Utc::now(),
+        },
+        team,
+        sprints,
+        failed_repositories: failures
+            .iter()
+            .map(|(repo, err)| JsonFailure {
+                repository: &repo.name,
+                error: err,
+            })
+            .collect(),
+        unmatched_authors: unmatched,
+        hotspots: hotspots
+            .iter()
+            .map(|(sprint, repos)| JsonSprintHotspots {
+                sprint: &sprint.name,
+                repositories: repos,
+            })
+            .collect(),
+        knowledge: knowledge
+            .iter()
+            .map(|(sprint, knowledge)| JsonSprintKnowledge {
+                sprint: &sprint.name,
+                knowledge,
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::analyzer::Analyzer;
+    use crate::analyze::hotspots::HotspotAnalyzer;
+    use crate::analyze::knowledge::KnowledgeAnalyzer;
+    use crate::analyze::{DataAnalysis, HotspotThresholds, KnowledgeThresholds};
+    use crate::forge::{ForgeUser, PullRequest, PullReview, ReviewState};
+    use crate::git::stats_cache::Churn;
+    use crate::git::Ownership;
+    use crate::model::{CoAuthorCredit, Sprint};
+    use crate::test_support::{commit_record, repository, user, BASE_TIME};
+
+    /// 2025-01-07, inside the sprint.
+    const SPRINT_TIME: i64 = BASE_TIME + 3 * 24 * 60 * 60;
+
+    fn sprint() -> Sprint {
+        Sprint {
+            name: "Sprint 1".to_string(),
+            since: DateTime::parse_from_rfc3339("2025-01-06T00:00:00+03:00").unwrap(),
+            until: DateTime::parse_from_rfc3339("2025-01-19T23:59:59+03:00").unwrap(),
+        }
+    }
+
+    /// Every section of the report filled by the analyzers.
+    fn data_analysis() -> DataAnalysis {
+        let app = repository("app");
+        let mut data = DataAnalysis::new(
+            vec![user("alice", "core"), user("bob", "core")],
+            vec![sprint()],
+            vec![app.clone()],
+            vec![],
+            vec![],
+            CoAuthorCredit::Full,
+        );
+        let mut feature = commit_record("alice", SPRINT_TIME, &["src/main.rs", "src/lib.rs"]);
+        feature.message = "feat(api): add endpoint".to_string();
+        feature.co_authors = vec!["bob@example.com".to_string()];
+        feature.churn = Some(Churn {
+            new_work: 1,
+            rework: [
+                ("alice@example.com".to_string(), 1),
+                ("bob@example.com".to_string(), 1),
+            ]
+            .into(),
+        });
+        let mut fix = commit_record("bob", SPRINT_TIME + 60, &["src/main.rs", "src/lib.rs"]);
+        fix.message = "fix!: drop legacy endpoint".to_string();
+        let mut bump = commit_record("bot", SPRINT_TIME + 120, &["Cargo.toml"]);
+        bump.message = "chore: bump".to_string();
+        data.insert_commits(&app, vec![feature, fix, bump]);
+
+        let at = |time| DateTime::from_timestamp(time, 0).unwrap().fixed_offset();
+        let review = |state, comments_count| {
+            PullReview::new(
+                Some(ForgeUser::new("bob", None::<String>)),
+                state,
+                comments_count,
+            )
+        };
+        let pull_request = PullRequest::new(
+            1,
+            ForgeUser::new("alice", None::<String>),
+            at(SPRINT_TIME),
+            Some(at(SPRINT_TIME + 3600)),
+            Some(at(SPRINT_TIME + 3600)),
+            vec![
+                review(ReviewState::Comment, 2),
+                review(ReviewState::Approved, 0),
+            ],
+        );
+        data.insert_pull_request(&app, vec![pull_request]);
+
+        let directories = [(
+            "src".to_string(),
+            [
+                ("alice@example.com".to_string(), 90),
+                ("bob@example.com".to_string(), 10),
+            ]
+            .into(),
+        )];
+        let ownership = Ownership {
+            at: sprint().until,
+            commit: "0".repeat(40),
+            directories: directories.into(),
+        };
+        data.insert_ownership(&app, vec![ownership]);
+        data.insert_failure(&repository("lib"), "Not found");
+        data
+    }
+
+    #[test]
+    fn renders_report_matching_schema() {
+        let data = data_analysis();
+        let sprints = data.analyze_sprints();
+        let unmatched = data.unmatched_authors();
+        let hotspots = data.analyze_hotspots("core", &HotspotThresholds::new(10, 1, 0, 20));
+        let knowledge = data.analyze_knowledge("core", &KnowledgeThresholds::new(80, 1, 1));
+        let alice = &sprints[0].1[0].1;
+        assert_eq!(alice.commits.rework_others, 1);
+        assert_eq!(alice.pull_requests.merged_pull_requests, 1);
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(hotspots[0].1[0].coupling.len(), 1);
+        assert_eq!(knowledge[0].1.bus_factor.len(), 1);
+
+        let json_str = render(
+            &sprints,
+            "core",
+            &data.failures,
+            &unmatched,
+            &hotspots,
+            &knowledge,
+        )
+        .unwrap();
+        let report: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        let schema: serde_json::Value =
+            serde_json::from_str(include_str!("../../schema/report.schema.json")).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let errors = validator
+            .iter_errors(&report)
+            .map(|error| format!("{}: {error}", error.instance_path))
+            .collect::<Vec<_>>();
+        assert!(errors.is_empty(), "{errors:#?}");
+        assert_eq!(report["schema_version"], SCHEMA_VERSION);
+    }
+}
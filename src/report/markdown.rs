@@ -1,39 +1,105 @@
-use crate::analyze::{SprintsAnalyzed, UserDataAnalyzed};
-use crate::model::{Sprint, User};
+use crate::analyze::{
+    CommitTypeStats, RepoFailures, RepoHotspots, SprintsAnalyzed, SprintsHotspots,
+    SprintsKnowledge, TeamKnowledge, UnmatchedAuthors, UserDataAnalyzed,
+};
+use crate::model::{Error, Result, Sprint, User};
+use crate::report::i18n::{Label, Lang};
 use itertools::Itertools;
-use markdown_builder::Markdown;
+use markdown_builder::{List, Markdown};
 use std::fs;
-use std::io::Write;
+use std::io;
 use markdown_table::{Heading, HeadingAlignment, MarkdownTable};
 
 pub trait MarkdownReport {
-    fn report_create(&self, team: &String);
+    fn report_create(
+        &self,
+        team: &str,
+        lang: Lang,
+        failures: &RepoFailures,
+        unmatched: &UnmatchedAuthors,
+        hotspots: &SprintsHotspots,
+        knowledge: &SprintsKnowledge,
+    ) -> Result<()>;
 }
 
 impl MarkdownReport for SprintsAnalyzed {
-    fn report_create(&self, team: &String) {
+    fn report_create(
+        &self,
+        team: &str,
+        lang: Lang,
+        failures: &RepoFailures,
+        unmatched: &UnmatchedAuthors,
+        hotspots: &SprintsHotspots,
+        knowledge: &SprintsKnowledge,
+    ) -> Result<()> {
+        let path = format!("{team}.md");
+        let report_error = |e| Error::report(&path, e);
         let mut doc = Markdown::new();
 
-        doc.h1("Спринты");
+        doc.header1(Label::Sprints.text(lang));
         for (sprint, data) in self {
             let data = data
                 .iter()
-                .filter(|(u, d)| u.teams.contains(&team))
+                .filter(|(u, _)| u.teams.iter().any(|t| t == team))
                 .collect::<Vec<_>>();
-            doc.add_sprint(sprint, data);
+            doc.add_sprint(sprint, data, lang).map_err(report_error)?;
+        }
+        if hotspots.iter().any(|(_, repos)| !repos.is_empty()) {
+            doc.add_hotspots(hotspots, lang).map_err(report_error)?;
+        }
+        if knowledge.iter().any(|(_, knowledge)| has_knowledge(knowledge)) {
+            doc.add_knowledge(knowledge, lang).map_err(report_error)?;
+        }
+        if !failures.is_empty() {
+            doc.add_failures(failures, lang);
+        }
+        if !unmatched.is_empty() {
+            doc.add_unmatched_authors(unmatched, lang).map_err(report_error)?;
         }
 
-        fs::write(format!("{team}.md"), doc.render()).unwrap();
+        fs::write(&path, doc.render()).map_err(|e| Error::report(&path, e))
     }
 }
 
 trait MarkdownExt {
-    fn add_sprint(&mut self, sprint: &Sprint, data: Vec<&(User, UserDataAnalyzed)>);
+    fn add_table(&mut self, md_table: &MarkdownTable<String>) -> io::Result<()>;
+    fn add_sprint(
+        &mut self,
+        sprint: &Sprint,
+        data: Vec<&(User, UserDataAnalyzed)>,
+        lang: Lang,
+    ) -> io::Result<()>;
+    fn add_team_commit_types(
+        &mut self,
+        data: &[&(User, UserDataAnalyzed)],
+        lang: Lang,
+    ) -> io::Result<()>;
+    fn add_failures(&mut self, failures: &RepoFailures, lang: Lang);
+    fn add_unmatched_authors(
+        &mut self,
+        unmatched: &UnmatchedAuthors,
+        lang: Lang,
+    ) -> io::Result<()>;
+    fn add_hotspots(&mut self, hotspots: &SprintsHotspots, lang: Lang) -> io::Result<()>;
+    fn add_repo_hotspots(&mut self, repo: &RepoHotspots, lang: Lang) -> io::Result<()>;
+    fn add_knowledge(&mut self, knowledge: &SprintsKnowledge, lang: Lang) -> io::Result<()>;
 }
 
 impl MarkdownExt for Markdown {
-    fn add_sprint(&mut self, sprint: &Sprint, data: Vec<&(User, UserDataAnalyzed)>) {
-        self.h2(format!(
+    fn add_table(&mut self, md_table: &MarkdownTable<String>) -> io::Result<()> {
+        let table = md_table.as_markdown().map_err(io::Error::other)?;
+        // `paragraph` word-wraps at 80 characters and would break the table rows.
+        self.elements.push(Box::new(table));
+        Ok(())
+    }
+
+    fn add_sprint(
+        &mut self,
+        sprint: &Sprint,
+        data: Vec<&(User, UserDataAnalyzed)>,
+        lang: Lang,
+    ) -> io::Result<()> {
+        self.header2(format!(
             "{} ({} - {})",
             sprint.name,
             sprint.since.format("%d.%m.%Y"),
@@ -77,75 +143,370 @@ impl MarkdownExt for Markdown {
                 )
             })
             .collect::<Vec<_>>();
-        table.push([vec!["Вклад в кодовую базу".to_string()], row].concat());
+        table.push([vec![Label::Contribution.text(lang).to_string()], row].concat());
+
+        let row = data
+            .iter()
+            .map(|(_, data)| data)
+            .map(|data| {
+                data.commits
+                    .languages
+                    .iter()
+                    .map(|(name, lines)| {
+                        format!(
+                            "{} *+{}* / *-{}*",
+                            Label::language(name, lang),
+                            lines.insertions,
+                            lines.deletions
+                        )
+                    })
+                    .join(", ")
+            })
+            .collect::<Vec<_>>();
+        table.push([vec![Label::Languages.text(lang).to_string()], row].concat());
+
+        let row = data
+            .iter()
+            .map(|(_, data)| data)
+            .map(|data| data.commits.new_work)
+            .map(|s| format!("{s}"))
+            .collect::<Vec<_>>();
+        table.push([vec![Label::NewWork.text(lang).to_string()], row].concat());
+
+        let row = data
+            .iter()
+            .map(|(_, data)| data)
+            .map(|data| data.commits.rework_own)
+            .map(|s| format!("{s}"))
+            .collect::<Vec<_>>();
+        table.push([vec![Label::ReworkOwn.text(lang).to_string()], row].concat());
 
         let row = data
             .iter()
             .map(|(_, data)| data)
-            .map(|data| data.commits.commits.clone())
+            .map(|data| data.commits.rework_others)
             .map(|s| format!("{s}"))
             .collect::<Vec<_>>();
-        table.push([vec!["Сделал коммитов".to_string()], row].concat());
+        table.push([vec![Label::ReworkOthers.text(lang).to_string()], row].concat());
 
         let row = data
             .iter()
             .map(|(_, data)| data)
-            .map(|data| data.pull_requests.create_pull_requests.clone())
+            .map(|data| data.commits.commits)
             .map(|s| format!("{s}"))
             .collect::<Vec<_>>();
-        table.push([vec!["Создал PR".to_string()], row].concat());
+        table.push([vec![Label::CommitsMade.text(lang).to_string()], row].concat());
 
         let row = data
             .iter()
             .map(|(_, data)| data)
-            .map(|data| data.pull_requests.merged_pull_requests.clone())
+            .map(|data| data.commits.co_authored_commits)
             .map(|s| format!("{s}"))
             .collect::<Vec<_>>();
-        table.push([vec!["Слил PR".to_string()], row].concat());
+        table.push([vec![Label::CoAuthoredCommits.text(lang).to_string()], row].concat());
+
+        let row = data
+            .iter()
+            .map(|(_, data)| data)
+            .map(|data| compliance(data.commits.conventional_commits, data.commits.commits))
+            .collect::<Vec<_>>();
+        table.push([vec![Label::ConventionalCommits.text(lang).to_string()], row].concat());
 
         let row = data
             .iter()
             .map(|(_, data)| data)
-            .map(|data| data.pull_requests.closed_pull_requests.clone())
+            .map(|data| data.commits.breaking_changes)
             .map(|s| format!("{s}"))
             .collect::<Vec<_>>();
-        table.push([vec!["Удалил PR".to_string()], row].concat());
+        table.push([vec![Label::BreakingChanges.text(lang).to_string()], row].concat());
+
+        let row = data
+            .iter()
+            .map(|(_, data)| data)
+            .map(|data| {
+                data.commits
+                    .commit_types
+                    .iter()
+                    .map(|(name, stats)| {
+                        format!("{name} **{}** (*{}*)", stats.commits, stats.change_lines)
+                    })
+                    .join(", ")
+            })
+            .collect::<Vec<_>>();
+        table.push([vec![Label::CommitTypes.text(lang).to_string()], row].concat());
 
         let row = data
             .iter()
             .map(|(_, data)| data)
-            .map(|data| data.pull_requests.received_discussions.clone())
+            .map(|data| data.pull_requests.create_pull_requests)
             .map(|s| format!("{s}"))
             .collect::<Vec<_>>();
-        table.push([vec!["Получил дискуссий".to_string()], row].concat());
+        table.push([vec![Label::PullRequestsCreated.text(lang).to_string()], row].concat());
 
         let row = data
             .iter()
             .map(|(_, data)| data)
-            .map(|data| data.pull_requests.approver_assigned.clone())
+            .map(|data| data.pull_requests.merged_pull_requests)
             .map(|s| format!("{s}"))
             .collect::<Vec<_>>();
-        table.push([vec!["Был назначен ревьювером".to_string()], row].concat());
+        table.push([vec![Label::PullRequestsMerged.text(lang).to_string()], row].concat());
 
         let row = data
             .iter()
             .map(|(_, data)| data)
-            .map(|data| data.pull_requests.approver_conducted.clone())
+            .map(|data| data.pull_requests.closed_pull_requests)
             .map(|s| format!("{s}"))
             .collect::<Vec<_>>();
-        table.push([vec!["Провел ревью".to_string()], row].concat());
+        table.push([vec![Label::PullRequestsClosed.text(lang).to_string()], row].concat());
 
         let row = data
             .iter()
             .map(|(_, data)| data)
-            .map(|data| data.pull_requests.approver_added_discussions.clone())
+            .map(|data| data.pull_requests.received_discussions)
             .map(|s| format!("{s}"))
             .collect::<Vec<_>>();
-        table.push([vec!["Завел дисскуссий".to_string()], row].concat());
+        table.push([vec![Label::DiscussionsReceived.text(lang).to_string()], row].concat());
+
+        let row = data
+            .iter()
+            .map(|(_, data)| data)
+            .map(|data| data.pull_requests.approver_assigned)
+            .map(|s| format!("{s}"))
+            .collect::<Vec<_>>();
+        table.push([vec![Label::ReviewerAssigned.text(lang).to_string()], row].concat());
+
+        let row = data
+            .iter()
+            .map(|(_, data)| data)
+            .map(|data| data.pull_requests.approver_conducted)
+            .map(|s| format!("{s}"))
+            .collect::<Vec<_>>();
+        table.push([vec![Label::ReviewsConducted.text(lang).to_string()], row].concat());
+
+        let row = data
+            .iter()
+            .map(|(_, data)| data)
+            .map(|data| data.pull_requests.approver_added_discussions)
+            .map(|s| format!("{s}"))
+            .collect::<Vec<_>>();
+        table.push([vec![Label::DiscussionsStarted.text(lang).to_string()], row].concat());
 
         let mut md_table = MarkdownTable::new(table);
         md_table.with_headings(header);
+        self.add_table(&md_table)?;
 
-        self.paragraph(md_table.as_markdown().unwrap());
+        self.add_team_commit_types(&data, lang)
     }
+
+    fn add_team_commit_types(
+        &mut self,
+        data: &[&(User, UserDataAnalyzed)],
+        lang: Lang,
+    ) -> io::Result<()> {
+        let commit_types = CommitTypeStats::team(data.iter().map(|(_, data)| &data.commits));
+        if commit_types.is_empty() {
+            return Ok(());
+        }
+        self.paragraph(format!("**{}**", Label::TeamCommitTypes.text(lang)));
+        let table = commit_types
+            .iter()
+            .map(|(name, stats)| {
+                vec![
+                    name.clone(),
+                    stats.commits.to_string(),
+                    stats.change_lines.to_string(),
+                    stats.scopes.iter().join(", "),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut md_table = MarkdownTable::new(table);
+        md_table.with_headings(vec![
+            Heading::new(Label::CommitType.text(lang).to_string(), None),
+            Heading::new(Label::TotalCommits.text(lang).to_string(), None),
+            Heading::new(Label::Lines.text(lang).to_string(), None),
+            Heading::new(Label::Scopes.text(lang).to_string(), None),
+        ]);
+        self.add_table(&md_table)?;
+        let conventional = data.iter().map(|(_, d)| d.commits.conventional_commits).sum();
+        let commits = data.iter().map(|(_, d)| d.commits.commits).sum();
+        self.paragraph(format!(
+            "{}: {}",
+            Label::ConventionalCommits.text(lang),
+            compliance(conventional, commits)
+        ));
+        Ok(())
+    }
+    fn add_failures(&mut self, failures: &RepoFailures, lang: Lang) {
+        self.header1(Label::FailedRepositories.text(lang));
+        self.paragraph(Label::FailedRepositoriesNote.text(lang));
+        let list = failures
+            .iter()
+            .fold(List::builder(), |list, (repo, err)| {
+                list.append(format!("**{}**: {}", repo.name, err))
+            })
+            .unordered();
+        self.list(list);
+    }
+
+    fn add_unmatched_authors(
+        &mut self,
+        unmatched: &UnmatchedAuthors,
+        lang: Lang,
+    ) -> io::Result<()> {
+        self.header1(Label::UnmatchedAuthors.text(lang));
+        self.paragraph(Label::UnmatchedAuthorsNote.text(lang));
+        let table = unmatched
+            .iter()
+            .map(|author| {
+                vec![
+                    author.email.clone(),
+                    author.commits.to_string(),
+                    author.change_lines.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut md_table = MarkdownTable::new(table);
+        md_table.with_headings(vec![
+            Heading::new(Label::Email.text(lang).to_string(), None),
+            Heading::new(Label::TotalCommits.text(lang).to_string(), None),
+            Heading::new(Label::Lines.text(lang).to_string(), None),
+        ]);
+        self.add_table(&md_table)?;
+        Ok(())
+    }
+
+    fn add_hotspots(&mut self, hotspots: &SprintsHotspots, lang: Lang) -> io::Result<()> {
+        self.header1(Label::Hotspots.text(lang));
+        self.paragraph(Label::HotspotsNote.text(lang));
+        for (sprint, repos) in hotspots.iter().filter(|(_, repos)| !repos.is_empty()) {
+            self.header2(format!(
+                "{} ({} - {})",
+                sprint.name,
+                sprint.since.format("%d.%m.%Y"),
+                sprint.until.format("%d.%m.%Y"),
+            ));
+            for repo in repos {
+                self.add_repo_hotspots(repo, lang)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_repo_hotspots(&mut self, repo: &RepoHotspots, lang: Lang) -> io::Result<()> {
+        self.header3(&repo.repository);
+        for (label, paths) in [
+            (Label::HotFiles, &repo.files),
+            (Label::HotDirectories, &repo.directories),
+        ] {
+            if paths.is_empty() {
+                continue;
+            }
+            self.paragraph(format!("**{}**", label.text(lang)));
+            let table = paths
+                .iter()
+                .map(|path| vec![format!("`{}`", path.path), path.commits.to_string()])
+                .collect::<Vec<_>>();
+            let mut md_table = MarkdownTable::new(table);
+            md_table.with_headings(vec![
+                Heading::new(Label::Path.text(lang).to_string(), None),
+                Heading::new(Label::TotalCommits.text(lang).to_string(), None),
+            ]);
+            self.add_table(&md_table)?;
+        }
+        if !repo.coupling.is_empty() {
+            self.paragraph(format!("**{}**", Label::ChangeCoupling.text(lang)));
+            let table = repo
+                .coupling
+                .iter()
+                .map(|coupled| {
+                    vec![
+                        format!("`{}`", coupled.first),
+                        format!("`{}`", coupled.second),
+                        coupled.commits.to_string(),
+                        coupled.degree.to_string(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let mut md_table = MarkdownTable::new(table);
+            md_table.with_headings(vec![
+                Heading::new(Label::Path.text(lang).to_string(), None),
+                Heading::new(Label::Path.text(lang).to_string(), None),
+                Heading::new(Label::TotalCommits.text(lang).to_string(), None),
+                Heading::new(Label::CouplingDegree.text(lang).to_string(), None),
+            ]);
+            self.add_table(&md_table)?;
+        }
+        Ok(())
+    }
+
+    fn add_knowledge(&mut self, knowledge: &SprintsKnowledge, lang: Lang) -> io::Result<()> {
+        self.header1(Label::Knowledge.text(lang));
+        self.paragraph(Label::KnowledgeNote.text(lang));
+        for (sprint, knowledge) in knowledge.iter().filter(|(_, k)| has_knowledge(k)) {
+            self.header2(format!(
+                "{} ({} - {})",
+                sprint.name,
+                sprint.since.format("%d.%m.%Y"),
+                sprint.until.format("%d.%m.%Y"),
+            ));
+            if !knowledge.bus_factor.is_empty() {
+                self.paragraph(format!("**{}**", Label::BusFactor.text(lang)));
+                let table = knowledge
+                    .bus_factor
+                    .iter()
+                    .map(|module| {
+                        vec![
+                            module.repository.clone(),
+                            format!("`{}`", module.module),
+                            module.owner.clone(),
+                            module.share.to_string(),
+                            module.lines.to_string(),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                let mut md_table = MarkdownTable::new(table);
+                md_table.with_headings(vec![
+                    Heading::new(Label::Repository.text(lang).to_string(), None),
+                    Heading::new(Label::Module.text(lang).to_string(), None),
+                    Heading::new(Label::Owner.text(lang).to_string(), None),
+                    Heading::new(Label::OwnerShare.text(lang).to_string(), None),
+                    Heading::new(Label::Lines.text(lang).to_string(), None),
+                ]);
+                self.add_table(&md_table)?;
+            }
+            self.paragraph(format!("**{}**", Label::Breadth.text(lang)));
+            let table = knowledge
+                .breadth
+                .iter()
+                .map(|user| {
+                    vec![
+                        user.username.clone(),
+                        user.repositories.join(", "),
+                        user.modules.len().to_string(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let mut md_table = MarkdownTable::new(table);
+            md_table.with_headings(vec![
+                Heading::new(Label::User.text(lang).to_string(), None),
+                Heading::new(Label::Repositories.text(lang).to_string(), None),
+                Heading::new(Label::Modules.text(lang).to_string(), None),
+            ]);
+            self.add_table(&md_table)?;
+        }
+        Ok(())
+    }
+}
+
+/// Conventional commits of all commits with the share, `3 / 4 (75%)`.
+fn compliance(conventional: usize, commits: usize) -> String {
+    match commits {
+        0 => "0".to_string(),
+        _ => format!("{conventional} / {commits} ({}%)", conventional * 100 / commits),
+    }
+}
+
+fn has_knowledge(knowledge: &TeamKnowledge) -> bool {
+    !knowledge.bus_factor.is_empty()
+        || knowledge.breadth.iter().any(|user| !user.repositories.is_empty())
 }
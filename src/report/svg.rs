@@ -0,0 +1,145 @@
+//! Minimal inline SVG charts for the HTML report, so it needs no scripts or CDN.
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 240.0;
+const MARGIN_LEFT: f64 = 48.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 16.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+const LEGEND_ROW: f64 = 18.0;
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+pub type Series = (String, Vec<usize>);
+
+/// A line per series across `labels`, e.g. a metric of every user across sprints.
+pub fn line_chart(labels: &[String], series: &[Series]) -> String {
+    let max = max_value(series);
+    let mut svg = chart_start(labels, series, max);
+    let step = step_x(labels.len());
+    for (index, (_, values)) in series.iter().enumerate() {
+        let color = color(index);
+        let points = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| format!("{:.1},{:.1}", point_x(i, step), point_y(*value, max)))
+            .collect::<Vec<_>>();
+        svg.push_str(&format!(
+            r#"<polyline fill="none" stroke="{color}" stroke-width="2" points="{}"/>"#,
+            points.join(" ")
+        ));
+        for (i, value) in values.iter().enumerate() {
+            svg.push_str(&format!(
+                r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{color}"><title>{}</title></circle>"#,
+                point_x(i, step),
+                point_y(*value, max),
+                value
+            ));
+        }
+    }
+    chart_end(svg, series)
+}
+
+/// Bars of every series grouped by `labels`, e.g. team totals per sprint.
+pub fn bar_chart(labels: &[String], series: &[Series]) -> String {
+    let max = max_value(series);
+    let mut svg = chart_start(labels, series, max);
+    let step = step_x(labels.len());
+    let bar_width = (step * 0.8 / series.len().max(1) as f64).max(1.0);
+    for (index, (_, values)) in series.iter().enumerate() {
+        let color = color(index);
+        for (i, value) in values.iter().enumerate() {
+            let x = point_x(i, step) - step * 0.4 + bar_width * index as f64;
+            let y = point_y(*value, max);
+            svg.push_str(&format!(
+                r#"<rect x="{x:.1}" y="{y:.1}" width="{bar_width:.1}" height="{:.1}" fill="{color}"><title>{}</title></rect>"#,
+                HEIGHT - MARGIN_BOTTOM - y,
+                value
+            ));
+        }
+    }
+    chart_end(svg, series)
+}
+
+fn chart_start(labels: &[String], series: &[Series], max: usize) -> String {
+    let height = HEIGHT + LEGEND_ROW * series.len().div_ceil(4) as f64;
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {WIDTH} {height}" width="{WIDTH}" height="{height}" font-family="sans-serif" font-size="11">"#
+    );
+    let bottom = HEIGHT - MARGIN_BOTTOM;
+    for tick in 0..=4 {
+        let value = max * tick / 4;
+        let y = point_y(value, max);
+        svg.push_str(&format!(
+            r##"<line x1="{MARGIN_LEFT}" x2="{:.1}" y1="{y:.1}" y2="{y:.1}" stroke="#ddd"/><text x="{:.1}" y="{:.1}" text-anchor="end">{value}</text>"##,
+            WIDTH - MARGIN_RIGHT,
+            MARGIN_LEFT - 6.0,
+            y + 4.0
+        ));
+    }
+    let step = step_x(labels.len());
+    for (i, label) in labels.iter().enumerate() {
+        svg.push_str(&format!(
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            point_x(i, step),
+            bottom + 16.0,
+            escape(label)
+        ));
+    }
+    svg
+}
+
+fn chart_end(mut svg: String, series: &[Series]) -> String {
+    let column = (WIDTH - MARGIN_LEFT) / 4.0;
+    for (index, (name, _)) in series.iter().enumerate() {
+        let x = MARGIN_LEFT + column * (index % 4) as f64;
+        let y = HEIGHT - 8.0 + LEGEND_ROW * (index / 4) as f64;
+        svg.push_str(&format!(
+            r#"<rect x="{x:.1}" y="{:.1}" width="10" height="10" fill="{}"/><text x="{:.1}" y="{y:.1}">{}</text>"#,
+            y - 9.0,
+            color(index),
+            x + 14.0,
+            escape(name)
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn max_value(series: &[Series]) -> usize {
+    let max = series
+        .iter()
+        .flat_map(|(_, values)| values)
+        .copied()
+        .max()
+        .unwrap_or(0);
+    // Round up to a multiple of 4 so every grid line gets a whole number.
+    max.max(1).div_ceil(4) * 4
+}
+
+fn step_x(count: usize) -> f64 {
+    (WIDTH - MARGIN_LEFT - MARGIN_RIGHT) / count.max(1) as f64
+}
+
+fn point_x(index: usize, step: f64) -> f64 {
+    MARGIN_LEFT + step * (index as f64 + 0.5)
+}
+
+fn point_y(value: usize, max: usize) -> f64 {
+    let bottom = HEIGHT - MARGIN_BOTTOM;
+    bottom - (bottom - MARGIN_TOP) * value as f64 / max as f64
+}
+
+fn color(index: usize) -> &'static str {
+    PALETTE[index % PALETTE.len()]
+}
+
+pub fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
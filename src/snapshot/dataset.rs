@@ -0,0 +1,169 @@
+use crate::analyze::DataAnalysis;
+use crate::forge::PullRequest;
+use crate::git::{Commit, Ownership};
+use crate::model::{
+    CoAuthorCredit, CommitType, Error, Language, Repository, Result, Sprint, User,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Bumped on every change of the snapshot layout, added fields included: an older snapshot would
+/// read them as zeros, so it is rejected instead.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Raw data collected by `fetch`, enough to analyze sprints again without network access.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Commits and pull requests older than this were not fetched.
+    pub since: DateTime<FixedOffset>,
+    /// Commits newer than this were not fetched.
+    pub until: DateTime<FixedOffset>,
+    pub repositories: Vec<RepositorySnapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepositorySnapshot {
+    pub repository: Repository,
+    pub commits: Vec<Commit>,
+    pub pull_requests: Vec<PullRequest>,
+    /// Empty unless the ownership was fetched.
+    pub ownership: Vec<Ownership>,
+    pub failure: Option<String>,
+}
+
+// Create
+impl Snapshot {
+    pub fn new(
+        data_analysis: &DataAnalysis,
+        since: DateTime<FixedOffset>,
+        until: DateTime<FixedOffset>,
+    ) -> Self {
+        let repositories = data_analysis
+            .repos
+            .iter()
+            .map(|repo| RepositorySnapshot {
+                repository: repo.clone(),
+                commits: data_analysis.commits.get(repo).cloned().unwrap_or_default(),
+                pull_requests: data_analysis
+                    .pull_requests
+                    .get(repo)
+                    .cloned()
+                    .unwrap_or_default(),
+                ownership: data_analysis.ownership.get(repo).cloned().unwrap_or_default(),
+                failure: data_analysis
+                    .failures
+                    .iter()
+                    .find(|(failed, _)| failed == repo)
+                    .map(|(_, err)| err.clone()),
+            })
+            .collect();
+        Self {
+            version: SNAPSHOT_VERSION,
+            created_at: Utc::now(),
+            since,
+            until,
+            repositories,
+        }
+    }
+
+    pub fn into_analysis(
+        self,
+        users: Vec<User>,
+        sprints: Vec<Sprint>,
+        languages: Vec<Language>,
+        commit_types: Vec<CommitType>,
+        co_author_credit: CoAuthorCredit,
+    ) -> DataAnalysis {
+        let repos = self
+            .repositories
+            .iter()
+            .map(|snapshot| snapshot.repository.clone())
+            .collect();
+        let mut data_analysis =
+            DataAnalysis::new(users, sprints, repos, languages, commit_types, co_author_credit);
+        for snapshot in self.repositories {
+            match snapshot.failure {
+                Some(failure) => data_analysis.insert_failure(&snapshot.repository, failure),
+                None => {
+                    data_analysis.insert_commits(&snapshot.repository, snapshot.commits);
+                    data_analysis.insert_pull_request(&snapshot.repository, snapshot.pull_requests);
+                    data_analysis.insert_ownership(&snapshot.repository, snapshot.ownership);
+                }
+            }
+        }
+        data_analysis
+    }
+}
+
+// Storage
+impl Snapshot {
+    pub fn load(path: &str) -> Result<Self> {
+        let json_str = fs::read_to_string(path).map_err(|e| Error::snapshot(path, e))?;
+        let version = serde_json::from_str::<serde_json::Value>(&json_str)
+            .map_err(|e| Error::snapshot(path, e))?["version"]
+            .as_u64();
+        if version != Some(SNAPSHOT_VERSION as u64) {
+            return Err(Error::snapshot(
+                path,
+                format!(
+                    "Unsupported snapshot version {} (expected {}), take it again with `fetch`",
+                    version.map_or("-".to_string(), |v| v.to_string()),
+                    SNAPSHOT_VERSION
+                ),
+            ));
+        }
+        serde_json::from_str(&json_str).map_err(|e| Error::snapshot(path, e))
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        let json_str = serde_json::to_string(self).map_err(|e| Error::snapshot(path, e))?;
+        fs::write(path, json_str).map_err(|e| Error::snapshot(path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_record, repository, BASE_TIME};
+    use tempfile::TempDir;
+
+    fn snapshot() -> Snapshot {
+        let app = repository("app");
+        let mut data = DataAnalysis::new(
+            vec![],
+            vec![],
+            vec![app.clone()],
+            vec![],
+            vec![],
+            CoAuthorCredit::Full,
+        );
+        data.insert_commits(&app, vec![commit_record("alice", BASE_TIME, &["a.rs"])]);
+        let since = DateTime::from_timestamp(BASE_TIME, 0)
+            .unwrap()
+            .fixed_offset();
+        Snapshot::new(&data, since, since)
+    }
+
+    #[test]
+    fn rejects_older_versions() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.json");
+        let path = path.to_str().unwrap();
+        snapshot().save(path).unwrap();
+        let loaded = Snapshot::load(path).unwrap();
+        assert_eq!(loaded.repositories[0].commits[0].files, vec!["a.rs"]);
+
+        let mut json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        json["version"] = 1.into();
+        fs::write(path, json.to_string()).unwrap();
+        let err = Snapshot::load(path).unwrap_err();
+        assert!(
+            err.to_string().contains("Unsupported snapshot version 1"),
+            "{err}"
+        );
+    }
+}
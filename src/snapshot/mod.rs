@@ -0,0 +1,3 @@
+pub mod dataset;
+
+pub use dataset::Snapshot;
@@ -0,0 +1,138 @@
+//! Fixtures shared by the tests.
+
+use crate::git::stats_cache::file_type;
+use crate::git::{Commit, CommitQuery, LineStats};
+use crate::model::{ForgeKind, MergePolicy, Repository, User};
+use chrono::DateTime;
+use git2::{IndexEntry, IndexTime, Oid, Signature, Time};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// 2025-01-04, inside the range of `query`.
+pub const BASE_TIME: i64 = 1_736_000_000;
+
+/// `group/{name}` on Gitea with every option at its default.
+pub fn repository(name: &str) -> Repository {
+    Repository {
+        name: name.to_string(),
+        ssh: format!("git@example.com:group/{name}.git"),
+        branch: "main".to_string(),
+        branches: vec![],
+        owner: "group".to_string(),
+        forge: ForgeKind::Gitea,
+        partial_clone: false,
+        merges: None,
+        include: vec![],
+        exclude: vec![],
+    }
+}
+
+/// Member of `team` committing as `{username}@example.com`.
+pub fn user(username: &str, team: &str) -> User {
+    User {
+        username: username.to_string(),
+        avatar_url: format!("https://example.com/{username}.png"),
+        role: "developer".to_string(),
+        teams: vec![team.to_string()],
+        emails: vec![format!("{username}@example.com")],
+        logins: vec![username.to_string()],
+        email_domains: vec![],
+        email_patterns: vec![],
+    }
+}
+
+/// Empty bare repository in a temporary directory.
+pub fn git_repository() -> (TempDir, git2::Repository) {
+    let dir = TempDir::new().unwrap();
+    let repo = git2::Repository::init_bare(dir.path()).unwrap();
+    repo.set_head("refs/heads/main").unwrap();
+    (dir, repo)
+}
+
+/// Commits a tree of exactly `files` by `{author}@example.com` at `time`, moving `branch` to
+/// the commit.
+pub fn commit(
+    repo: &git2::Repository,
+    branch: &str,
+    parents: &[Oid],
+    author: &str,
+    time: i64,
+    files: &[(&str, &str)],
+) -> Oid {
+    let mut index = repo.index().unwrap();
+    index.clear().unwrap();
+    for (path, content) in files {
+        let entry = IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: 0o100644,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id: Oid::zero(),
+            flags: 0,
+            flags_extended: 0,
+            path: path.as_bytes().to_vec(),
+        };
+        index.add_frombuffer(&entry, content.as_bytes()).unwrap();
+    }
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parents = parents
+        .iter()
+        .map(|id| repo.find_commit(*id).unwrap())
+        .collect::<Vec<_>>();
+    let email = format!("{author}@example.com");
+    let signature = Signature::new(author, &email, &Time::new(time, 0)).unwrap();
+    let id = repo
+        .commit(
+            None,
+            &signature,
+            &signature,
+            "change",
+            &tree,
+            &parents.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+    repo.reference(&format!("refs/heads/{branch}"), id, true, "")
+        .unwrap();
+    id
+}
+
+/// Commit as read from history, changing one line in each of `files`.
+pub fn commit_record(author: &str, time: i64, files: &[&str]) -> Commit {
+    let mut extensions: BTreeMap<String, LineStats> = BTreeMap::new();
+    for file in files {
+        extensions
+            .entry(file_type(Path::new(file)))
+            .or_default()
+            .insertions += 1;
+    }
+    Commit {
+        email: format!("{author}@example.com"),
+        message: "change".to_string(),
+        files_changed: files.len(),
+        insertions: files.len(),
+        deletions: 0,
+        extensions,
+        files: files.iter().map(|file| file.to_string()).collect(),
+        co_authors: vec![],
+        churn: None,
+        datetime: DateTime::from_timestamp(time, 0).unwrap().fixed_offset(),
+    }
+}
+
+/// `main` in 2025.
+pub fn query(merges: MergePolicy) -> CommitQuery {
+    CommitQuery::new(
+        vec!["main".to_string()],
+        DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap(),
+        DateTime::parse_from_rfc3339("2025-12-31T00:00:00Z").unwrap(),
+        0,
+        merges,
+        vec![],
+        vec![],
+    )
+}
@@ -3,6 +3,12 @@ use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
 pub trait MultiProgressNew {
     fn add_with_style(&self, pb: ProgressBar, style: ProgressStyle) -> ProgressBar;
+    fn insert_after_with_style(
+        &self,
+        after: &ProgressBar,
+        pb: ProgressBar,
+        style: ProgressStyle,
+    ) -> ProgressBar;
 }
 
 impl MultiProgressNew for MultiProgress {
@@ -12,4 +18,16 @@ impl MultiProgressNew for MultiProgress {
         pb.enable_steady_tick(Duration::from_millis(100));
         pb
     }
+
+    fn insert_after_with_style(
+        &self,
+        after: &ProgressBar,
+        pb: ProgressBar,
+        style: ProgressStyle,
+    ) -> ProgressBar {
+        let pb = self.insert_after(after, pb);
+        pb.set_style(style);
+        pb.enable_steady_tick(Duration::from_millis(100));
+        pb
+    }
 }
@@ -2,7 +2,6 @@ use indicatif::ProgressStyle;
 
 const ONLY_MESSAGE_TEMPLATE: &str = "{spinner} {wide_msg}";
 const PERCENT_TEMPLATE: &str = "{spinner} {msg:15} {wide_bar:} {pos:>3}/{len:3}%";
-const NUMBER_TEMPLATE: &str = "{spinner} {msg:15} {wide_bar:} {pos:>7}/{len}";
 
 pub struct ProgressStyleTemplate;
 
@@ -16,10 +15,4 @@ impl ProgressStyleTemplate {
             .unwrap()
             .progress_chars("#>-")
     }
-
-    pub fn number_bar() -> ProgressStyle {
-        ProgressStyle::with_template(NUMBER_TEMPLATE)
-            .unwrap()
-            .progress_chars("#>-")
-    }
 }